@@ -0,0 +1,50 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use micrograd_rs::autograd;
+use micrograd_rs::prelude::*;
+use micrograd_rs::{Conv2D, Layer};
+
+fn build_conv() -> (Conv2D, Tensor<Ix4>) {
+    let conv: Conv2D = Conv2D::new("conv2d", 3, 8, (3, 3), (1, 1), (1, 1), (1, 1));
+    let input = Tensor::from_shape_fn((2, 3, 16, 16), |(b, c, h, w)| {
+        val!(((b + c + h * w) % 7) as f64 / 7.0 - 0.5)
+    });
+
+    (conv, input)
+}
+
+fn conv2d_forward(c: &mut Criterion) {
+    let (conv, input) = build_conv();
+    let mut group = c.benchmark_group("conv2d_forward");
+    group.sample_size(10);
+
+    group.bench_function("graph", |b| b.iter(|| conv.forward(&input).sum().value()));
+    group.bench_function("tape", |b| {
+        b.iter(|| {
+            let _tape = autograd::tape();
+            conv.forward(&input).sum().value()
+        })
+    });
+
+    group.finish();
+}
+
+fn conv2d_forward_backward(c: &mut Criterion) {
+    let (conv, input) = build_conv();
+    let mut group = c.benchmark_group("conv2d_forward_backward");
+    group.sample_size(10);
+
+    group.bench_function("graph", |b| {
+        b.iter(|| conv.forward(&input).sum().backward())
+    });
+    group.bench_function("tape", |b| {
+        b.iter(|| {
+            let _tape = autograd::tape();
+            conv.forward(&input).sum().backward()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, conv2d_forward, conv2d_forward_backward);
+criterion_main!(benches);
@@ -1,5 +1,6 @@
 extern crate micrograd_rs;
 use micrograd_rs::activations as Activation;
+use micrograd_rs::autograd::no_grad;
 use micrograd_rs::pooling::MaxPool;
 use micrograd_rs::prelude::*;
 use micrograd_rs::{Conv2D, Layer, Linear, Model, Sequential};
@@ -44,7 +45,8 @@ fn main() {
 
     let test_data = Array4::from_shape_vec((500, 1, 28, 28), tst_img)
         .expect("Error converting images to Array4 struct:")
-        .map(|x| Value::from((*x as f32) / 256.0));
+        .mapv(|x| x as f64 / 256.0);
+    let test_data: Tensor<Ix4> = Tensor::from_f64(&test_data, false);
 
     let test_labels = Array2::from_shape_vec((500, 1), tst_lbl)
         .expect("Error converting labels to Array2 struct:");
@@ -80,7 +82,8 @@ fn main() {
 
     let label = *y.first().unwrap() as usize;
 
-    let outputs = model.forward(&x.to_owned()).mapv(|v| v.value());
+    let _guard = no_grad();
+    let outputs = model.forward(&x.to_owned()).values();
     let (predicted, probability) = outputs
         .into_iter()
         .enumerate()
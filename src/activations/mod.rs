@@ -1,4 +1,4 @@
-use crate::{tensor::Tensor, Layer};
+use crate::{tensor::Tensor, Float, Layer};
 use ndarray::Dimension;
 
 mod relu;
@@ -11,6 +11,6 @@ pub use sigmoid::Sigmoid;
 pub use softmax::Softmax;
 pub use tanh::Tanh;
 
-pub trait Activation<D: Dimension>: Layer<D, D> {
-    fn activate(&self, inputs: &Tensor<D>) -> Tensor<D>;
+pub trait Activation<D: Dimension, F: Float = f64>: Layer<D, D, F> {
+    fn activate(&self, inputs: &Tensor<D, F>) -> Tensor<D, F>;
 }
@@ -3,31 +3,35 @@ use ndarray::Dimension;
 use super::Activation;
 use crate::ops::UnaryOps;
 use crate::tensor::Tensor;
+use crate::tensor_value::TensorValue;
 use crate::value::Value;
-use crate::Layer;
+use crate::{Float, Layer};
 
 pub struct ReLU;
 
-impl<D> Activation<D> for ReLU
+impl<D, F> Activation<D, F> for ReLU
 where
     D: Dimension,
+    F: Float,
 {
-    fn activate(&self, unactivated: &Tensor<D>) -> Tensor<D> {
-        unactivated.mapv(|value| {
-            let activated = value.value().max(0.0);
-            Value::with_op(activated, UnaryOps::ReLU(value))
-        })
+    fn activate(&self, unactivated: &Tensor<D, F>) -> Tensor<D, F> {
+        unactivated.mapv(|value| Value::unary(UnaryOps::ReLU(value)))
     }
 }
 
-impl<D> Layer<D, D> for ReLU
+impl<D, F> Layer<D, D, F> for ReLU
 where
     D: Dimension,
+    F: Float,
 {
-    fn forward(&self, input: &Tensor<D>) -> Tensor<D> {
+    fn forward(&self, input: &Tensor<D, F>) -> Tensor<D, F> {
         self.activate(input)
     }
 
+    fn forward_value(&self, input: &TensorValue<D, F>) -> TensorValue<D, F> {
+        input.relu()
+    }
+
     fn name(&self) -> String {
         String::from("ReLU")
     }
@@ -1,29 +1,35 @@
 use ndarray::Dimension;
 
 use super::Activation;
-use crate::prelude::{One, Value};
 use crate::tensor::Tensor;
-use crate::Layer;
+use crate::tensor_value::TensorValue;
+use crate::{Float, Layer};
 
 pub struct Sigmoid;
 
-impl<D> Activation<D> for Sigmoid
+impl<D, F> Activation<D, F> for Sigmoid
 where
     D: Dimension,
+    F: Float,
 {
-    fn activate(&self, unactivated: &Tensor<D>) -> Tensor<D> {
-        unactivated.mapv(|value| Value::one() / (Value::one() + (-&value).exp()))
+    fn activate(&self, unactivated: &Tensor<D, F>) -> Tensor<D, F> {
+        unactivated.mapv(|value| value.sigmoid())
     }
 }
 
-impl<D> Layer<D, D> for Sigmoid
+impl<D, F> Layer<D, D, F> for Sigmoid
 where
     D: Dimension,
+    F: Float,
 {
-    fn forward(&self, input: &Tensor<D>) -> Tensor<D> {
+    fn forward(&self, input: &Tensor<D, F>) -> Tensor<D, F> {
         self.activate(input)
     }
 
+    fn forward_value(&self, input: &TensorValue<D, F>) -> TensorValue<D, F> {
+        input.sigmoid()
+    }
+
     fn name(&self) -> String {
         String::from("Sigmoid")
     }
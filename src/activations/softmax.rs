@@ -6,30 +6,30 @@ use crate::{prelude::*, Layer};
 pub struct Softmax(pub usize);
 
 impl Softmax {
-    fn softmax(&self, logits: &[Value]) -> Vec<Value> {
+    fn softmax<F: Float>(&self, logits: &[Value<F>]) -> Vec<Value<F>> {
         let max_logit = logits.iter().cloned().reduce(Value::max).unwrap();
         let exp_sum = logits
             .iter()
             .map(|logit| (logit - &max_logit).exp())
-            .sum::<Value>();
+            .sum::<Value<F>>();
 
         logits
             .iter()
             .map(|logit| &(logit - &max_logit).exp() / &exp_sum)
-            .collect::<Vec<Value>>()
+            .collect::<Vec<Value<F>>>()
     }
 
-    fn dimension_factor<D: Dimension>(&self, inputs: &Tensor<D>) -> usize {
+    fn dimension_factor<D: Dimension, F: Float>(&self, inputs: &Tensor<D, F>) -> usize {
         let n = inputs.ndim();
         let shape = inputs.shape();
 
         shape.to_vec().iter().rev().take(n - self.0 - 1).product()
     }
 
-    fn ordered_by_dimension<D: Dimension>(&self, inputs: &Tensor<D>) -> Vec<Value> {
+    fn ordered_by_dimension<D: Dimension, F: Float>(&self, inputs: &Tensor<D, F>) -> Vec<Value<F>> {
         let dimension_factor = self.dimension_factor(inputs);
 
-        let mut inputs: Vec<(usize, Value)> = inputs
+        let mut inputs: Vec<(usize, Value<F>)> = inputs
             .clone()
             .into_raw_vec()
             .into_iter()
@@ -41,11 +41,12 @@ impl Softmax {
     }
 }
 
-impl<D> Activation<D> for Softmax
+impl<D, F> Activation<D, F> for Softmax
 where
     D: Dimension + RemoveAxis,
+    F: Float,
 {
-    fn activate(&self, inputs: &Tensor<D>) -> Tensor<D> {
+    fn activate(&self, inputs: &Tensor<D, F>) -> Tensor<D, F> {
         let dim = inputs.raw_dim();
 
         let shape = inputs.shape();
@@ -70,11 +71,12 @@ where
     }
 }
 
-impl<D> Layer<D, D> for Softmax
+impl<D, F> Layer<D, D, F> for Softmax
 where
     D: Dimension + RemoveAxis,
+    F: Float,
 {
-    fn forward(&self, input: &Tensor<D>) -> Tensor<D> {
+    fn forward(&self, input: &Tensor<D, F>) -> Tensor<D, F> {
         self.activate(input)
     }
 
@@ -1,27 +1,33 @@
 use ndarray::Dimension;
 
 use super::Activation;
-use crate::{tensor::Tensor, Layer};
+use crate::{tensor::Tensor, Float, Layer, TensorValue};
 
 pub struct Tanh;
 
-impl<D> Activation<D> for Tanh
+impl<D, F> Activation<D, F> for Tanh
 where
     D: Dimension,
+    F: Float,
 {
-    fn activate(&self, unactivated: &Tensor<D>) -> Tensor<D> {
-        unactivated.mapv(|value| ((&value * &2.0).exp() - 1.0) / ((&value * &2.0).exp() + 1.0))
+    fn activate(&self, unactivated: &Tensor<D, F>) -> Tensor<D, F> {
+        unactivated.mapv(|value| value.tanh())
     }
 }
 
-impl<D> Layer<D, D> for Tanh
+impl<D, F> Layer<D, D, F> for Tanh
 where
     D: Dimension,
+    F: Float,
 {
-    fn forward(&self, input: &Tensor<D>) -> Tensor<D> {
+    fn forward(&self, input: &Tensor<D, F>) -> Tensor<D, F> {
         self.activate(input)
     }
 
+    fn forward_value(&self, input: &TensorValue<D, F>) -> TensorValue<D, F> {
+        input.tanh()
+    }
+
     fn name(&self) -> String {
         String::from("Tanh")
     }
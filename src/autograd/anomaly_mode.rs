@@ -0,0 +1,63 @@
+use std::cell::Cell;
+
+thread_local! {
+    static ANOMALY_ENABLED: Cell<bool> = const { Cell::new(false) };
+    static NAN_POLICY: Cell<NanPolicy> = const { Cell::new(NanPolicy::Panic) };
+}
+
+/// What happens when an operation produces a NaN outside of anomaly mode.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NanPolicy {
+    /// Panic with the op kind and operand values that produced the NaN.
+    #[default]
+    Panic,
+    /// Let NaN and infinite values flow through the graph so the caller can
+    /// check `value().is_finite()` and skip the step.
+    Propagate,
+}
+
+/// Sets the NaN policy of the current thread until it is dropped, at which
+/// point the previous policy is restored.
+pub struct NanPolicyGuard {
+    prev: NanPolicy,
+}
+
+impl Drop for NanPolicyGuard {
+    fn drop(&mut self) {
+        NAN_POLICY.with(|nan_policy| nan_policy.set(self.prev));
+    }
+}
+
+/// Applies `policy` while the returned guard is alive, e.g.
+/// `let _policy = set_nan_policy(NanPolicy::Propagate);`.
+pub fn set_nan_policy(policy: NanPolicy) -> NanPolicyGuard {
+    let prev = NAN_POLICY.with(|nan_policy| nan_policy.replace(policy));
+    NanPolicyGuard { prev }
+}
+
+pub fn nan_policy() -> NanPolicy {
+    NAN_POLICY.with(Cell::get)
+}
+
+/// Enables anomaly detection on the current thread until it is dropped.
+/// While enabled, every node records the op and operand values it was created
+/// from, and any NaN or infinite value produced by the forward or backward
+/// pass panics with the offending op and its path through the graph.
+pub struct AnomalyGuard {
+    prev: bool,
+}
+
+impl Drop for AnomalyGuard {
+    fn drop(&mut self) {
+        ANOMALY_ENABLED.with(|enabled| enabled.set(self.prev));
+    }
+}
+
+pub fn detect_anomaly() -> AnomalyGuard {
+    let prev = ANOMALY_ENABLED.with(|enabled| enabled.replace(true));
+    AnomalyGuard { prev }
+}
+
+pub fn is_anomaly_enabled() -> bool {
+    ANOMALY_ENABLED.with(Cell::get)
+}
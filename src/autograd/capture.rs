@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+use std::slice;
+
+use super::engine::{self, Grad};
+use crate::float::Float;
+use crate::ops::binary_ops::BinaryKind;
+use crate::ops::unary_ops::UnaryKind;
+use crate::ops::{MatMulOps, Op, Ops};
+use crate::value::{NodeId, Value};
+
+#[derive(Clone, Copy)]
+enum Prim<F> {
+    Unary(UnaryKind<F>),
+    Binary(BinaryKind),
+}
+
+impl<F: Float> Prim<F> {
+    fn from_op(operation: &Ops<F>) -> Self {
+        match operation {
+            Ops::Binary(op) => Prim::Binary(op.kind()),
+            Ops::Unary(op) => Prim::Unary(op.kind()),
+            Ops::MatMul(_) => unreachable!("Matrix products are compiled separately"),
+            Ops::Custom(op) => panic!("Custom op {} cannot be captured", Op::name(op)),
+            Ops::NoOp => unreachable!("Leaves are not compiled into instructions"),
+        }
+    }
+
+    fn is_binary(&self) -> bool {
+        matches!(self, Prim::Binary(_))
+    }
+
+    fn forward(&self, x: F, y: F) -> F {
+        match *self {
+            Prim::Unary(kind) => kind.forward(x),
+            Prim::Binary(kind) => kind.forward(x, y),
+        }
+    }
+
+    fn partials(&self, x: F, y: F, out: F) -> [F; 2] {
+        match *self {
+            Prim::Unary(kind) => [kind.derivative(x, out), F::zero()],
+            Prim::Binary(kind) => kind.partials(x, y, out),
+        }
+    }
+}
+
+enum Instr<F> {
+    Prim {
+        prim: Prim<F>,
+        operands: [usize; 2],
+        output: usize,
+        requires_grad: bool,
+    },
+    // Elements the output does not depend on are never captured, so they
+    // have no slot to write to.
+    MatMul {
+        lhs: Vec<usize>,
+        rhs: Vec<usize>,
+        dims: (usize, usize, usize),
+        outputs: Vec<Option<usize>>,
+        requires_grad: bool,
+    },
+}
+
+/// A graph compiled into a flat list of primitive ops over a buffer of
+/// values, built by [`capture`].
+pub struct Program<F: Float = f64> {
+    values: Vec<F>,
+    grads: Vec<F>,
+    instrs: Vec<Instr<F>>,
+    inputs: Vec<Option<usize>>,
+    params: Vec<(usize, Value<F>)>,
+    output: usize,
+}
+
+/// Compiles the graph behind `output` so it can be replayed with new values
+/// for `inputs` without building any new nodes.
+///
+/// Every other leaf that requires grad is treated as a parameter: its value
+/// is read on each [`Program::forward`] and [`Program::backward`] accumulates
+/// into its gradient. Remaining leaves are frozen as constants, and so is any
+/// control flow taken while building the graph, such as the branch picked by
+/// `Value::max`. Hooks and retained gradients are not replayed.
+pub fn capture<F: Float>(output: &Value<F>, inputs: &[Value<F>]) -> Program<F> {
+    output.assert_graph("autograd::capture");
+
+    let topo_order = engine::topo_sort(slice::from_ref(output));
+    let mut slots: HashMap<NodeId, usize> = HashMap::with_capacity(topo_order.len());
+    let mut values = Vec::with_capacity(topo_order.len());
+    let mut instrs = vec![];
+    let mut params = vec![];
+    let mut products = HashMap::new();
+
+    for node in &topo_order {
+        let slot = values.len();
+        slots.insert(node.id(), slot);
+        values.push(node.value());
+
+        let operation = node.operation();
+        if let Ops::NoOp = *operation {
+            let is_input = inputs.iter().any(|input| input.id() == node.id());
+            if node.should_compute_grad() && !is_input {
+                params.push((slot, node.clone()));
+            }
+            continue;
+        }
+
+        let operands = operation
+            .variables()
+            .into_iter()
+            .map(|operand| slots[&operand.id()])
+            .collect::<Vec<usize>>();
+
+        match operation {
+            Ops::MatMul(MatMulOps::Product { lhs, dims, .. }) => {
+                products.insert(node.id(), instrs.len());
+                instrs.push(Instr::MatMul {
+                    rhs: operands[lhs.len()..].to_vec(),
+                    lhs: operands[..lhs.len()].to_vec(),
+                    dims: *dims,
+                    outputs: vec![None; dims.0 * dims.2],
+                    requires_grad: node.should_compute_grad(),
+                });
+            }
+            Ops::MatMul(MatMulOps::Element(product, idx)) => {
+                if let Instr::MatMul { outputs, .. } = &mut instrs[products[&product.id()]] {
+                    outputs[*idx] = Some(slot);
+                }
+            }
+            operation => instrs.push(Instr::Prim {
+                prim: Prim::from_op(operation),
+                operands: [operands[0], *operands.last().unwrap()],
+                output: slot,
+                requires_grad: node.should_compute_grad(),
+            }),
+        }
+    }
+
+    Program {
+        grads: vec![F::zero(); values.len()],
+        inputs: inputs
+            .iter()
+            .map(|input| slots.get(&input.id()).copied())
+            .collect(),
+        output: slots[&output.id()],
+        values,
+        instrs,
+        params,
+    }
+}
+
+impl<F: Float> Program<F> {
+    /// Re-runs the forward pass on new input values, in the order they were
+    /// given to [`capture`], and returns the output.
+    pub fn forward(&mut self, inputs: &[F]) -> F {
+        assert!(
+            inputs.len() == self.inputs.len(),
+            "Program expects {} inputs, got {}",
+            self.inputs.len(),
+            inputs.len()
+        );
+
+        for (slot, value) in self.inputs.iter().zip(inputs) {
+            if let Some(slot) = slot {
+                self.values[*slot] = *value;
+            }
+        }
+        for (slot, param) in &self.params {
+            self.values[*slot] = param.value();
+        }
+        for instr in &self.instrs {
+            match instr {
+                Instr::Prim {
+                    prim,
+                    operands: [lhs, rhs],
+                    output,
+                    ..
+                } => self.values[*output] = prim.forward(self.values[*lhs], self.values[*rhs]),
+                Instr::MatMul {
+                    lhs,
+                    rhs,
+                    dims: (_, k, n),
+                    outputs,
+                    ..
+                } => {
+                    for (idx, output) in outputs.iter().enumerate() {
+                        let Some(output) = output else {
+                            continue;
+                        };
+
+                        let (i, j) = (idx / n, idx % n);
+                        self.values[*output] = (0..*k)
+                            .map(|t| self.values[lhs[i * k + t]] * self.values[rhs[t * n + j]])
+                            .sum();
+                    }
+                }
+            }
+        }
+
+        self.output()
+    }
+
+    /// Backpropagates through the last forward pass and accumulates into the
+    /// gradients of the parameters.
+    pub fn backward(&mut self) {
+        self.grads.fill(F::zero());
+        self.grads[self.output] = F::one();
+
+        for instr in self.instrs.iter().rev() {
+            match instr {
+                Instr::Prim {
+                    prim,
+                    operands: [lhs, rhs],
+                    output,
+                    requires_grad,
+                } => {
+                    let grad = self.grads[*output];
+                    if grad.is_zero() || !requires_grad {
+                        continue;
+                    }
+
+                    let (x, y) = (self.values[*lhs], self.values[*rhs]);
+                    let partials = prim.partials(x, y, self.values[*output]);
+
+                    self.grads[*lhs] += grad * partials[0];
+                    if prim.is_binary() {
+                        self.grads[*rhs] += grad * partials[1];
+                    }
+                }
+                Instr::MatMul {
+                    lhs,
+                    rhs,
+                    dims: (_, k, n),
+                    outputs,
+                    requires_grad,
+                } => {
+                    if !requires_grad {
+                        continue;
+                    }
+
+                    for (idx, output) in outputs.iter().enumerate() {
+                        let grad = output.map_or_else(F::zero, |output| self.grads[output]);
+                        if grad.is_zero() {
+                            continue;
+                        }
+
+                        let (i, j) = (idx / n, idx % n);
+                        for t in 0..*k {
+                            let (x, y) = (lhs[i * k + t], rhs[t * n + j]);
+                            self.grads[x] += grad * self.values[y];
+                            self.grads[y] += grad * self.values[x];
+                        }
+                    }
+                }
+            }
+        }
+
+        for (slot, param) in &self.params {
+            param.accumulate_grad(Grad::Raw(self.grads[*slot]));
+        }
+    }
+
+    pub fn output(&self) -> F {
+        self.values[self.output]
+    }
+
+    /// Gradient of the output with respect to each input after the last
+    /// backward pass, zero for inputs the output does not depend on.
+    pub fn input_grads(&self) -> Vec<F> {
+        self.inputs
+            .iter()
+            .map(|slot| slot.map_or_else(F::zero, |slot| self.grads[slot]))
+            .collect()
+    }
+
+    pub fn parameters(&self) -> Vec<Value<F>> {
+        self.params.iter().map(|(_, param)| param.clone()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.instrs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instrs.is_empty()
+    }
+}
@@ -0,0 +1,89 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write as _;
+use std::path::Path;
+use std::{fs, io};
+
+use crate::float::Float;
+use crate::ops::Op;
+use crate::value::{NodeId, Value};
+
+pub struct DotOptions {
+    pub max_depth: Option<usize>,
+    pub show_grad: bool,
+}
+
+impl Default for DotOptions {
+    fn default() -> Self {
+        DotOptions {
+            max_depth: None,
+            show_grad: true,
+        }
+    }
+}
+
+impl<F: Float> Value<F> {
+    pub fn to_dot(&self) -> String {
+        self.to_dot_with(&DotOptions::default())
+    }
+
+    pub fn to_dot_with(&self, options: &DotOptions) -> String {
+        self.assert_graph("Value::to_dot");
+        let mut ids: HashMap<NodeId, usize> = HashMap::from([(self.id(), 0)]);
+        let mut queue = VecDeque::from([(self.clone(), 0)]);
+        let mut nodes = String::new();
+        let mut edges = String::new();
+
+        while let Some((node, depth)) = queue.pop_front() {
+            let idx = ids[&node.id()];
+            writeln!(nodes, "  n{idx} [{}];", node_attributes(&node, options)).unwrap();
+
+            let operands = node.operation().visible_operands();
+            if operands.is_empty() {
+                continue;
+            }
+
+            if options
+                .max_depth
+                .is_some_and(|max_depth| depth >= max_depth)
+            {
+                writeln!(
+                    nodes,
+                    "  n{idx}_collapsed [label=\"...\", shape=plaintext];"
+                )
+                .unwrap();
+                writeln!(edges, "  n{idx}_collapsed -> n{idx} [style=dashed];").unwrap();
+                continue;
+            }
+
+            for operand in &operands {
+                let next_idx = ids.len();
+                let operand_idx = *ids.entry(operand.id()).or_insert_with(|| {
+                    queue.push_back((operand.clone(), depth + 1));
+                    next_idx
+                });
+                writeln!(edges, "  n{operand_idx} -> n{idx};").unwrap();
+            }
+        }
+
+        format!("digraph {{\n  rankdir=BT;\n{nodes}{edges}}}\n")
+    }
+
+    pub fn write_dot<P: AsRef<Path>>(&self, path: P, options: &DotOptions) -> io::Result<()> {
+        fs::write(path, self.to_dot_with(options))
+    }
+}
+
+fn node_attributes<F: Float>(node: &Value<F>, options: &DotOptions) -> String {
+    let name = node.operation().name().replace('"', "\\\"");
+    let mut label = format!("{name}\\nvalue: {}", node.value());
+
+    if options.show_grad {
+        match node.grad() {
+            Some(grad) => write!(label, "\\ngrad: {}", grad.value()).unwrap(),
+            None => label.push_str("\\ngrad: None"),
+        }
+    }
+
+    let shape = if node.is_leaf() { "ellipse" } else { "box" };
+    format!("label=\"{label}\", shape={shape}")
+}
@@ -0,0 +1,275 @@
+use std::collections::{HashMap, HashSet};
+use std::iter::zip;
+
+use super::{is_anomaly_enabled, nan_policy, no_grad, NanPolicy};
+use crate::float::Float;
+use crate::ops::{MatMulOps, Op, Ops};
+use crate::value::{NodeId, Value};
+
+#[derive(Clone)]
+pub(crate) enum Grad<F: Float> {
+    Raw(F),
+    Graph(Value<F>),
+}
+
+impl<F: Float> Grad<F> {
+    pub(crate) fn one(create_graph: bool) -> Self {
+        match create_graph {
+            true => Grad::Graph(Value::new(F::one())),
+            false => Grad::Raw(F::one()),
+        }
+    }
+
+    pub(crate) fn into_value(self) -> Value<F> {
+        match self {
+            Grad::Raw(grad) => Value::new(grad),
+            Grad::Graph(grad) => grad,
+        }
+    }
+
+    fn value(&self) -> F {
+        match self {
+            Grad::Raw(grad) => *grad,
+            Grad::Graph(grad) => grad.value(),
+        }
+    }
+
+    fn accumulate(self, other: Grad<F>) -> Self {
+        match (self, other) {
+            (Grad::Raw(prev), Grad::Raw(grad)) => Grad::Raw(prev + grad),
+            (prev, grad) => Grad::Graph(prev.into_value() + grad.into_value()),
+        }
+    }
+}
+
+pub(crate) struct GradTable<F: Float>(HashMap<NodeId, Grad<F>>);
+
+impl<F: Float> Default for GradTable<F> {
+    fn default() -> Self {
+        GradTable(HashMap::new())
+    }
+}
+
+impl<F: Float> GradTable<F> {
+    pub(crate) fn accumulate(&mut self, node: &Value<F>, grad: Grad<F>) {
+        let grad = match self.0.remove(&node.id()) {
+            Some(prev) => prev.accumulate(grad),
+            None => grad,
+        };
+
+        self.0.insert(node.id(), grad);
+    }
+
+    pub(crate) fn insert(&mut self, node: &Value<F>, grad: Grad<F>) {
+        self.0.insert(node.id(), grad);
+    }
+
+    pub(crate) fn get(&self, node: &Value<F>) -> Option<Grad<F>> {
+        self.0.get(&node.id()).cloned()
+    }
+}
+
+pub(crate) fn topo_sort<F: Float>(roots: &[Value<F>]) -> Vec<Value<F>> {
+    let mut order = vec![];
+    let mut visited = HashSet::new();
+    let mut stack = roots
+        .iter()
+        .rev()
+        .map(|root| (root.clone(), false))
+        .collect::<Vec<(Value<F>, bool)>>();
+
+    while let Some((node, expanded)) = stack.pop() {
+        if expanded {
+            order.push(node);
+            continue;
+        }
+
+        if !visited.insert(node.id()) {
+            continue;
+        }
+
+        let operands = node
+            .operation()
+            .variables()
+            .into_iter()
+            .filter(|operand| !visited.contains(&operand.id()))
+            .cloned()
+            .collect::<Vec<Value<F>>>();
+
+        stack.push((node, true));
+        stack.extend(operands.into_iter().map(|operand| (operand, false)));
+    }
+
+    order
+}
+
+/// Walks the graph behind `roots` in reverse topological order, seeding every
+/// root with a gradient of one. Returns the visited nodes together with the
+/// gradient each of them received; the nodes themselves are left untouched.
+pub(crate) fn execute<F: Float>(
+    roots: &[Value<F>],
+    create_graph: bool,
+) -> (Vec<Value<F>>, GradTable<F>) {
+    let seeds = roots.iter().map(|_| Grad::one(create_graph)).collect();
+    execute_from(roots, seeds)
+}
+
+/// Same as [`execute`], but starts every root from its own gradient.
+pub(crate) fn execute_from<F: Float>(
+    roots: &[Value<F>],
+    seeds: Vec<Grad<F>>,
+) -> (Vec<Value<F>>, GradTable<F>) {
+    let topo_order = topo_sort(roots);
+    let mut grads = GradTable::default();
+    let mut parents = is_anomaly_enabled().then(HashMap::new);
+    let mut products: HashMap<NodeId, Vec<Option<Grad<F>>>> = HashMap::new();
+
+    for (root, seed) in zip(roots, seeds) {
+        grads.accumulate(root, seed);
+    }
+
+    for source in topo_order.iter().rev() {
+        let operation = source.operation();
+
+        // Matrix products come after all of their elements in reverse
+        // topological order, so every element gradient has been collected.
+        if let Ops::MatMul(op @ MatMulOps::Product { .. }) = operation {
+            if let Some(output_grads) = products.remove(&source.id()) {
+                let operand_grads = op.backward(output_grads);
+
+                for (idx, (operand, grad)) in zip(op.variables(), operand_grads).enumerate() {
+                    if operand.should_compute_grad() {
+                        check_grad(source, idx, grad.value(), parents.as_ref());
+                        record_parent(parents.as_mut(), source, operand);
+                        grads.accumulate(operand, grad);
+                    }
+                }
+            }
+            continue;
+        }
+
+        let Some(grad) = grads.get(source) else {
+            continue;
+        };
+        let grad = run_hooks(source, grad);
+        grads.insert(source, grad.clone());
+
+        if let Ops::MatMul(MatMulOps::Element(product, idx)) = operation {
+            if product.should_compute_grad() {
+                let len = match product.operation() {
+                    Ops::MatMul(op) => op.len(),
+                    _ => unreachable!("Matrix elements always point at their product"),
+                };
+
+                record_parent(parents.as_mut(), source, product);
+                products
+                    .entry(product.id())
+                    .or_insert_with(|| vec![None; len])[*idx] = Some(grad);
+            }
+            continue;
+        }
+
+        let operands = operation.variables();
+
+        match grad {
+            Grad::Graph(grad) => {
+                let operand_grads = operation.propagate(source, &grad);
+
+                for (idx, (operand, operand_grad)) in zip(operands, operand_grads).enumerate() {
+                    if let Some(operand_grad) = operand_grad {
+                        check_grad(source, idx, operand_grad.value(), parents.as_ref());
+                        record_parent(parents.as_mut(), source, operand);
+                        grads.accumulate(operand, Grad::Graph(operand_grad));
+                    }
+                }
+            }
+            Grad::Raw(grad) => {
+                let partials = operation.partials(source);
+
+                for (idx, (operand, partial)) in zip(operands, partials).enumerate() {
+                    if operand.should_compute_grad() {
+                        check_grad(source, idx, grad * partial, parents.as_ref());
+                        record_parent(parents.as_mut(), source, operand);
+                        grads.accumulate(operand, Grad::Raw(grad * partial));
+                    }
+                }
+            }
+        }
+    }
+
+    (topo_order, grads)
+}
+
+// Hooks see a node's gradient once every contribution has been accumulated,
+// and may replace it before it is propagated any further.
+fn run_hooks<F: Float>(node: &Value<F>, grad: Grad<F>) -> Grad<F> {
+    let hooks = node.hooks();
+    if hooks.is_empty() {
+        return grad;
+    }
+
+    let create_graph = matches!(grad, Grad::Graph(_));
+    let _guard = (!create_graph).then(no_grad);
+
+    let mut grad = grad.into_value();
+    for hook in hooks {
+        if let Some(new_grad) = hook(&grad) {
+            grad = new_grad;
+        }
+    }
+
+    match create_graph {
+        true => Grad::Graph(grad),
+        false => Grad::Raw(grad.value()),
+    }
+}
+
+fn record_parent<F: Float>(
+    parents: Option<&mut HashMap<NodeId, Value<F>>>,
+    source: &Value<F>,
+    operand: &Value<F>,
+) {
+    if let Some(parents) = parents {
+        parents
+            .entry(operand.id())
+            .or_insert_with(|| source.clone());
+    }
+}
+
+fn check_grad<F: Float>(
+    source: &Value<F>,
+    idx: usize,
+    grad: F,
+    parents: Option<&HashMap<NodeId, Value<F>>>,
+) {
+    if grad.is_finite() {
+        return;
+    }
+
+    match parents {
+        Some(parents) => panic!(
+            "Anomaly detected: backward of {} produced {grad} for operand {idx}\n  node path: {}",
+            source.describe(),
+            node_path(source, parents)
+        ),
+        None if grad.is_nan() && nan_policy() == NanPolicy::Panic => panic!(
+            "Gradient cannot be NaN: backward of {} produced NaN for operand {idx}",
+            source.describe()
+        ),
+        None => (),
+    }
+}
+
+// Follows the first parent recorded for each node back up to the root.
+fn node_path<F: Float>(node: &Value<F>, parents: &HashMap<NodeId, Value<F>>) -> String {
+    let mut path = vec![node.operation().name()];
+    let mut curr = node;
+
+    while let Some(parent) = parents.get(&curr.id()) {
+        path.push(parent.operation().name());
+        curr = parent;
+    }
+
+    path.reverse();
+    path.join(" -> ")
+}
@@ -0,0 +1,36 @@
+use crate::float::Float;
+use crate::tensor::{Tensor, TensorGrad};
+use crate::value::Value;
+use ndarray::{Array, Dimension, Zip};
+
+/// Evaluates `f` at `primals` and returns its outputs together with the
+/// Jacobian-vector product along `tangents`, computed in a single forward pass.
+///
+/// `f` receives fresh dual leaves holding the values of `primals`, so the
+/// outputs do not backpropagate into the original inputs.
+pub fn jvp<D, E, F, Func>(
+    f: Func,
+    primals: &Tensor<D, F>,
+    tangents: &Array<F, D>,
+) -> (Tensor<E, F>, Array<F, E>)
+where
+    D: Dimension,
+    E: Dimension,
+    F: Float,
+    Func: FnOnce(&Tensor<D, F>) -> Tensor<E, F>,
+{
+    assert_eq!(
+        primals.shape(),
+        tangents.shape(),
+        "Tangents must have the same shape as the primals"
+    );
+
+    let duals = Zip::from(primals)
+        .and(tangents)
+        .map_collect(|primal, tangent| Value::dual(primal.value(), *tangent));
+
+    let outputs = f(&duals);
+    let output_tangents = outputs.tangents();
+
+    (outputs, output_tangents)
+}
@@ -0,0 +1,48 @@
+use super::grad::grad;
+use crate::float::Float;
+use crate::value::Value;
+use ndarray::Array2;
+
+/// Jacobian of the outputs of `f` with respect to `inputs`, one row per output.
+pub fn jacobian<F, Func>(f: Func, inputs: &[Value<F>]) -> Array2<F>
+where
+    F: Float,
+    Func: Fn(&[Value<F>]) -> Vec<Value<F>>,
+{
+    let outputs = f(inputs);
+    for output in &outputs {
+        output.assert_graph("autograd::jacobian");
+    }
+    let mut jacobian = Array2::zeros((outputs.len(), inputs.len()));
+
+    for (output, mut row) in outputs.into_iter().zip(jacobian.rows_mut()) {
+        let grads = grad(&[output], inputs, false);
+        for (entry, grad) in row.iter_mut().zip(grads) {
+            *entry = grad.value();
+        }
+    }
+
+    jacobian
+}
+
+/// Hessian of the scalar output of `f` with respect to `inputs`, built by
+/// differentiating each entry of the gradient graph a second time.
+pub fn hessian<F, Func>(f: Func, inputs: &[Value<F>]) -> Array2<F>
+where
+    F: Float,
+    Func: Fn(&[Value<F>]) -> Value<F>,
+{
+    let output = f(inputs);
+    output.assert_graph("autograd::hessian");
+    let first = grad(&[output], inputs, true);
+    let mut hessian = Array2::zeros((inputs.len(), inputs.len()));
+
+    for (partial, mut row) in first.into_iter().zip(hessian.rows_mut()) {
+        let grads = grad(&[partial], inputs, false);
+        for (entry, grad) in row.iter_mut().zip(grads) {
+            *entry = grad.value();
+        }
+    }
+
+    hessian
+}
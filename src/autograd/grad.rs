@@ -0,0 +1,30 @@
+use super::engine::{self, Grad};
+use crate::float::Float;
+use crate::value::Value;
+
+/// Computes the gradients of the sum of `outputs` with respect to `inputs`
+/// without writing to the `grad` of any node in the graph. Inputs the outputs
+/// do not depend on receive a gradient of zero.
+///
+/// With `create_graph` the returned gradients are themselves differentiable,
+/// which allows higher-order derivatives such as Hessian-vector products.
+pub fn grad<F: Float>(
+    outputs: &[Value<F>],
+    inputs: &[Value<F>],
+    create_graph: bool,
+) -> Vec<Value<F>> {
+    for value in outputs.iter().chain(inputs) {
+        value.assert_graph("autograd::grad");
+    }
+    let (_, grads) = engine::execute(outputs, create_graph);
+
+    inputs
+        .iter()
+        .map(|input| {
+            grads
+                .get(input)
+                .unwrap_or(Grad::Raw(F::zero()))
+                .into_value()
+        })
+        .collect()
+}
@@ -0,0 +1,28 @@
+use std::cell::Cell;
+
+thread_local! {
+    static GRAD_ENABLED: Cell<bool> = const { Cell::new(true) };
+}
+
+/// Disables graph construction on the current thread until it is dropped,
+/// at which point the previous grad mode is restored.
+pub struct NoGradGuard {
+    prev: bool,
+}
+
+impl Drop for NoGradGuard {
+    fn drop(&mut self) {
+        GRAD_ENABLED.with(|enabled| enabled.set(self.prev));
+    }
+}
+
+/// Operations performed while the returned guard is alive produce plain leaf
+/// values instead of graph nodes, e.g. `let _guard = no_grad();`.
+pub fn no_grad() -> NoGradGuard {
+    let prev = GRAD_ENABLED.with(|enabled| enabled.replace(false));
+    NoGradGuard { prev }
+}
+
+pub fn is_grad_enabled() -> bool {
+    GRAD_ENABLED.with(Cell::get)
+}
@@ -0,0 +1,128 @@
+use super::grad::grad;
+use super::grad_mode::no_grad;
+use crate::float::Float;
+use crate::layers::Layer;
+use crate::tensor::Tensor;
+use crate::value::Value;
+use ndarray::Dimension;
+use std::fmt;
+
+/// The input whose analytical gradient strayed furthest from its central
+/// finite difference.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GradMismatch<F: Float = f64> {
+    pub input: usize,
+    pub analytical: F,
+    pub numerical: F,
+    pub error: F,
+}
+
+impl<F: Float> fmt::Display for GradMismatch<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Gradient mismatch for input {}: analytical {}, numerical {} (error {})",
+            self.input, self.analytical, self.numerical, self.error
+        )
+    }
+}
+
+/// Compares the gradients of `f` with respect to `inputs` against central
+/// finite differences with step `eps`.
+///
+/// The error of each input is the absolute difference scaled down by the
+/// larger gradient magnitude once it exceeds one. Returns the worst offending
+/// input if its error is above `tol`.
+pub fn gradcheck<F, Func>(
+    f: Func,
+    inputs: &[Value<F>],
+    eps: F,
+    tol: F,
+) -> Result<(), GradMismatch<F>>
+where
+    F: Float,
+    Func: Fn(&[Value<F>]) -> Value<F>,
+{
+    let analytical = grad(&[f(inputs)], inputs, false);
+
+    let _guard = no_grad();
+    let mut worst: Option<GradMismatch<F>> = None;
+
+    for (idx, (input, analytical)) in inputs.iter().zip(analytical).enumerate() {
+        let original = input.value();
+
+        *input.value_mut() = original + eps;
+        let forward = f(inputs).value();
+        *input.value_mut() = original - eps;
+        let backward = f(inputs).value();
+        *input.value_mut() = original;
+
+        let analytical = analytical.value();
+        let numerical = (forward - backward) / (eps + eps);
+        let scale = F::one().max(analytical.abs()).max(numerical.abs());
+        let error = match (analytical - numerical).abs() / scale {
+            error if error.is_nan() => F::infinity(),
+            error => error,
+        };
+
+        if worst.is_none_or(|worst| error > worst.error) {
+            worst = Some(GradMismatch {
+                input: idx,
+                analytical,
+                numerical,
+                error,
+            });
+        }
+    }
+
+    match worst {
+        Some(worst) if worst.error > tol => Err(worst),
+        _ => Ok(()),
+    }
+}
+
+/// Runs [`gradcheck`] on `layer` over the elements of `input` followed by
+/// `layer.parameters()`, so mismatch indices past `input.len()` point into
+/// the parameters.
+///
+/// The outputs are reduced with distinct weights rather than a plain sum so
+/// that layers whose outputs sum to a constant are still checked. The
+/// layer's buffers, such as BatchNorm running statistics, are restored once
+/// the check is done.
+pub fn gradcheck_layer<D, F, L>(
+    layer: &L,
+    input: &Tensor<D, F>,
+    eps: F,
+    tol: F,
+) -> Result<(), GradMismatch<F>>
+where
+    D: Dimension,
+    F: Float,
+    L: Layer<D, D, F> + ?Sized,
+{
+    let n = input.len();
+
+    let mut inputs = input
+        .iter()
+        .map(|value| Value::new(value.value()))
+        .collect::<Vec<_>>();
+    inputs.extend(layer.parameters());
+
+    let reduce = |values: &[Value<F>]| {
+        let input = Tensor::from_shape_vec(input.raw_dim(), values[..n].to_vec()).unwrap();
+        let output = layer.forward(&input);
+        let len = F::cast(output.len() as f64);
+
+        output
+            .iter()
+            .enumerate()
+            .map(|(idx, value)| value.clone() * F::cast((idx + 1) as f64) / len)
+            .sum()
+    };
+
+    let buffers = layer.buffers();
+    let result = gradcheck(reduce, &inputs, eps, tol);
+    layer.set_buffers(&buffers);
+
+    result
+}
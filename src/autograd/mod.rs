@@ -0,0 +1,29 @@
+mod anomaly_mode;
+mod capture;
+mod dot;
+pub(crate) mod engine;
+mod forward;
+mod functional;
+mod grad;
+mod grad_mode;
+mod gradcheck;
+pub(crate) mod profiler;
+#[cfg(feature = "tape")]
+pub(crate) mod tape;
+
+pub use self::anomaly_mode::{
+    detect_anomaly, is_anomaly_enabled, nan_policy, set_nan_policy, AnomalyGuard, NanPolicy,
+    NanPolicyGuard,
+};
+pub use self::capture::{capture, Program};
+pub use self::dot::DotOptions;
+pub use self::forward::jvp;
+pub use self::functional::{hessian, jacobian};
+pub use self::grad::grad;
+pub use self::grad_mode::{is_grad_enabled, no_grad, NoGradGuard};
+pub use self::gradcheck::{gradcheck, gradcheck_layer, GradMismatch};
+pub use self::profiler::{
+    is_profiling, live_nodes, profile, LayerStats, ProfileGuard, ProfileStats,
+};
+#[cfg(feature = "tape")]
+pub use self::tape::{is_recording, tape, TapeGuard};
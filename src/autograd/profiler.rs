@@ -0,0 +1,225 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use indexmap::IndexMap;
+
+static LIVE_NODES: AtomicUsize = AtomicUsize::new(0);
+// Profiles alive on any thread, so that building nodes only looks at the
+// thread-local stats while someone is profiling.
+static PROFILES: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static STATS: RefCell<Option<ProfileStats>> = const { RefCell::new(None) };
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LayerStats {
+    pub calls: usize,
+    pub nodes: usize,
+    pub time: Duration,
+}
+
+/// What the current thread did while a [`ProfileGuard`] was alive.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProfileStats {
+    /// Graph and tape nodes allocated, leaves included.
+    pub nodes: usize,
+    /// Most graph nodes alive at once, as counted by [`live_nodes`].
+    pub peak_live_nodes: usize,
+    /// Nodes created per op, keyed by op name.
+    pub ops: IndexMap<String, usize>,
+    pub backward_calls: usize,
+    pub backward_time: Duration,
+    /// Breakdown of `Sequential::forward` per layer name.
+    pub layers: IndexMap<String, LayerStats>,
+}
+
+impl fmt::Display for ProfileStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "nodes: {} (peak live {})",
+            self.nodes, self.peak_live_nodes
+        )?;
+        writeln!(
+            f,
+            "backward: {} calls in {:?}",
+            self.backward_calls, self.backward_time
+        )?;
+
+        let mut ops = self.ops.iter().collect::<Vec<_>>();
+        ops.sort_by(|(_, a), (_, b)| b.cmp(a));
+        for (name, count) in ops {
+            writeln!(f, "  {name:<12} {count:>10}")?;
+        }
+
+        for (name, layer) in &self.layers {
+            writeln!(
+                f,
+                "  {name:<12} {:>10} nodes in {} calls, {:?}",
+                layer.nodes, layer.calls, layer.time
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ProfileStats {
+    fn merge(&mut self, inner: ProfileStats) {
+        self.nodes += inner.nodes;
+        self.peak_live_nodes = self.peak_live_nodes.max(inner.peak_live_nodes);
+        for (name, count) in inner.ops {
+            *self.ops.entry(name).or_default() += count;
+        }
+        self.backward_calls += inner.backward_calls;
+        self.backward_time += inner.backward_time;
+        for (name, layer) in inner.layers {
+            let outer = self.layers.entry(name).or_default();
+            outer.calls += layer.calls;
+            outer.nodes += layer.nodes;
+            outer.time += layer.time;
+        }
+    }
+}
+
+/// Profiles the current thread until it is dropped, at which point its stats
+/// are folded into any enclosing profile, which then resumes, e.g. `let profile = profile();` and later
+/// `println!("{}", profile.stats())`.
+pub struct ProfileGuard {
+    prev: Option<ProfileStats>,
+}
+
+impl ProfileGuard {
+    pub fn stats(&self) -> ProfileStats {
+        STATS.with(|stats| stats.borrow().clone().unwrap_or_default())
+    }
+}
+
+impl Drop for ProfileGuard {
+    fn drop(&mut self) {
+        STATS.with(|stats| {
+            let inner = stats.borrow_mut().take();
+            let mut prev = self.prev.take();
+            if let (Some(prev), Some(inner)) = (prev.as_mut(), inner) {
+                prev.merge(inner);
+            }
+            *stats.borrow_mut() = prev;
+        });
+        PROFILES.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+pub fn profile() -> ProfileGuard {
+    PROFILES.fetch_add(1, Ordering::Relaxed);
+    let stats = ProfileStats {
+        peak_live_nodes: live_nodes(),
+        ..Default::default()
+    };
+    let prev = STATS.with(|curr| curr.replace(Some(stats)));
+
+    ProfileGuard { prev }
+}
+
+pub fn is_profiling() -> bool {
+    STATS.with(|stats| stats.borrow().is_some())
+}
+
+/// Graph nodes currently allocated across every thread, out of those built
+/// while any thread was profiling.
+pub fn live_nodes() -> usize {
+    LIVE_NODES.load(Ordering::Relaxed)
+}
+
+fn with_stats(f: impl FnOnce(&mut ProfileStats)) {
+    if PROFILES.load(Ordering::Relaxed) == 0 {
+        return;
+    }
+
+    STATS.with(|stats| {
+        if let Some(stats) = stats.borrow_mut().as_mut() {
+            f(stats);
+        }
+    });
+}
+
+pub(crate) fn record_op(name: impl FnOnce() -> String) {
+    with_stats(|stats| *stats.ops.entry(name()).or_default() += 1);
+}
+
+#[cfg(feature = "tape")]
+pub(crate) fn record_tape_node() {
+    with_stats(|stats| stats.nodes += 1);
+}
+
+pub(crate) fn time_backward<R>(backward: impl FnOnce() -> R) -> R {
+    if !is_profiling() {
+        return backward();
+    }
+
+    let start = Instant::now();
+    let result = backward();
+    let elapsed = start.elapsed();
+
+    with_stats(|stats| {
+        stats.backward_calls += 1;
+        stats.backward_time += elapsed;
+    });
+    result
+}
+
+pub(crate) fn time_layer<R>(name: impl FnOnce() -> String, forward: impl FnOnce() -> R) -> R {
+    if !is_profiling() {
+        return forward();
+    }
+
+    let nodes = STATS.with(|stats| stats.borrow().as_ref().map_or(0, |stats| stats.nodes));
+    let start = Instant::now();
+    let result = forward();
+    let elapsed = start.elapsed();
+
+    with_stats(|stats| {
+        let created = stats.nodes.saturating_sub(nodes);
+        let layer = stats.layers.entry(name()).or_default();
+        layer.calls += 1;
+        layer.nodes += created;
+        layer.time += elapsed;
+    });
+    result
+}
+
+/// Counts the node it is embedded in for as long as that node is alive, as
+/// long as it was built while some thread was profiling.
+pub(crate) struct NodeCounter(bool);
+
+impl Default for NodeCounter {
+    fn default() -> Self {
+        if PROFILES.load(Ordering::Relaxed) == 0 {
+            return NodeCounter(false);
+        }
+
+        let live = LIVE_NODES.fetch_add(1, Ordering::Relaxed) + 1;
+        with_stats(|stats| {
+            stats.nodes += 1;
+            stats.peak_live_nodes = stats.peak_live_nodes.max(live);
+        });
+
+        NodeCounter(true)
+    }
+}
+
+impl NodeCounter {
+    pub(crate) fn hidden() -> Self {
+        NodeCounter(false)
+    }
+}
+
+impl Drop for NodeCounter {
+    fn drop(&mut self) {
+        if self.0 {
+            LIVE_NODES.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+}
@@ -0,0 +1,331 @@
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::mem;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use ndarray::{Array2, ArrayView2};
+
+use super::engine::{self, Grad};
+use super::profiler;
+use super::{is_anomaly_enabled, nan_policy, NanPolicy};
+use crate::float::Float;
+use crate::ops::{Op, Ops};
+use crate::tensor::TensorGrad;
+use crate::value::{NodeId, Value};
+
+// Ids handed out to tapes on every thread, so a handle only ever matches the
+// tape it was recorded on. 0 means no tape is recording.
+static NEXT_TAPE_ID: AtomicU32 = AtomicU32::new(1);
+
+thread_local! {
+    static TAPE: RefCell<Option<Box<dyn Any>>> = const { RefCell::new(None) };
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+    static TAPE_ID: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Index of a node on the tape with id `tape_id`. The id tells apart handles
+/// that outlived their tape or were sent to another thread.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct TapeRef {
+    tape_id: u32,
+    index: u32,
+}
+
+struct Node<F> {
+    value: F,
+    requires_grad: bool,
+    edges: (u32, u32),
+}
+
+// Nodes and their (operand, partial) edges live in two contiguous buffers.
+// Graph values used as operands are mirrored by a leaf node and kept alive
+// in `externals` so their gradients can be handed back after a sweep.
+struct TapeData<F: Float> {
+    nodes: Vec<Node<F>>,
+    edges: Vec<(u32, F)>,
+    externals: Vec<(u32, Value<F>)>,
+    external_ids: HashMap<NodeId, u32>,
+}
+
+impl<F: Float> Default for TapeData<F> {
+    fn default() -> Self {
+        TapeData {
+            nodes: vec![],
+            edges: vec![],
+            externals: vec![],
+            external_ids: HashMap::new(),
+        }
+    }
+}
+
+impl<F: Float> TapeData<F> {
+    fn push(&mut self, value: F, requires_grad: bool) -> u32 {
+        profiler::record_tape_node();
+        let index = self.nodes.len() as u32;
+        let edges = (self.edges.len() as u32, self.edges.len() as u32);
+        self.nodes.push(Node {
+            value,
+            requires_grad,
+            edges,
+        });
+        index
+    }
+
+    fn node(&self, tape_ref: TapeRef) -> &Node<F> {
+        &self.nodes[tape_ref.index as usize]
+    }
+}
+
+/// Records every op on the current thread's tape while alive. Once the
+/// outermost guard is dropped the tape is cleared and any value recorded on
+/// it can no longer be used, e.g. `let _tape = tape();`.
+pub struct TapeGuard {
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for TapeGuard {
+    fn drop(&mut self) {
+        let depth = DEPTH.with(|depth| depth.get() - 1);
+        DEPTH.with(|curr| curr.set(depth));
+
+        if depth == 0 {
+            let tape = TAPE.with(|tape| tape.borrow_mut().take());
+            TAPE_ID.with(|id| id.set(0));
+            mem::drop(tape);
+        }
+    }
+}
+
+/// Switches the current thread to the tape engine: ops are appended to a
+/// contiguous buffer instead of allocating graph nodes, and `backward` becomes
+/// a single reverse sweep over it. Values created outside the tape, such as
+/// layer parameters, still receive their gradients as usual.
+///
+/// Tape values do not support hooks, retained gradients, forward-mode
+/// tangents or `create_graph`. Only available with the `tape` feature, which
+/// keeps `Value` a single pointer when the tape is not needed.
+pub fn tape() -> TapeGuard {
+    if !is_recording() {
+        let id = match NEXT_TAPE_ID.fetch_add(1, Ordering::Relaxed) {
+            0 => NEXT_TAPE_ID.fetch_add(1, Ordering::Relaxed),
+            id => id,
+        };
+        TAPE_ID.with(|curr| curr.set(id));
+    }
+    DEPTH.with(|depth| depth.set(depth.get() + 1));
+    TapeGuard {
+        _not_send: PhantomData,
+    }
+}
+
+pub fn is_recording() -> bool {
+    DEPTH.with(Cell::get) > 0
+}
+
+fn current_tape_id() -> u32 {
+    TAPE_ID.with(Cell::get)
+}
+
+fn with_tape<F: Float, R>(f: impl FnOnce(&mut TapeData<F>) -> R) -> R {
+    TAPE.with(|tape| {
+        let mut tape = tape.borrow_mut();
+        let tape = tape
+            .get_or_insert_with(|| Box::new(TapeData::<F>::default()))
+            .downcast_mut::<TapeData<F>>()
+            .expect("Tape is already recording values of another float type");
+        f(tape)
+    })
+}
+
+fn check_tape_id(tape_ref: TapeRef) {
+    if tape_ref.tape_id != current_tape_id() {
+        panic!("Tape value used after its tape was dropped or on another thread");
+    }
+}
+
+pub(crate) fn value<F: Float>(tape_ref: TapeRef) -> F {
+    check_tape_id(tape_ref);
+    with_tape(|tape: &mut TapeData<F>| tape.node(tape_ref).value)
+}
+
+pub(crate) fn requires_grad<F: Float>(tape_ref: TapeRef) -> bool {
+    check_tape_id(tape_ref);
+    with_tape(|tape: &mut TapeData<F>| tape.node(tape_ref).requires_grad)
+}
+
+pub(crate) fn is_leaf<F: Float>(tape_ref: TapeRef) -> bool {
+    check_tape_id(tape_ref);
+    with_tape(|tape: &mut TapeData<F>| {
+        let (start, end) = tape.node(tape_ref).edges;
+        start == end
+    })
+}
+
+pub(crate) fn push_constant<F: Float>(value: F) -> TapeRef {
+    let index = with_tape(|tape: &mut TapeData<F>| tape.push(value, false));
+    TapeRef {
+        tape_id: current_tape_id(),
+        index,
+    }
+}
+
+pub(crate) fn push_op<F: Float>(value: F, operation: &Ops<F>) -> TapeRef {
+    let operands = operation
+        .variables()
+        .into_iter()
+        .map(operand_index)
+        .collect::<Vec<(u32, bool)>>();
+    let requires_grad = operands.iter().any(|(_, requires_grad)| *requires_grad);
+
+    let index = with_tape(|tape: &mut TapeData<F>| tape.push(value, requires_grad));
+    let tape_ref = TapeRef {
+        tape_id: current_tape_id(),
+        index,
+    };
+    if !requires_grad {
+        return tape_ref;
+    }
+
+    let partials = operation.partials(&Value::from_tape(tape_ref));
+    with_tape(|tape: &mut TapeData<F>| {
+        let start = tape.edges.len() as u32;
+        for ((operand, requires_grad), partial) in operands.into_iter().zip(partials) {
+            if requires_grad {
+                tape.edges.push((operand, partial));
+            }
+        }
+        tape.nodes[index as usize].edges = (start, tape.edges.len() as u32);
+    });
+
+    tape_ref
+}
+
+/// Records the product of `a` and `b`, whose entries are `values`, as one
+/// node per element with an edge to every operand in its row and column.
+pub(crate) fn push_matmul<F: Float>(
+    a: ArrayView2<Value<F>>,
+    b: ArrayView2<Value<F>>,
+    values: &Array2<F>,
+) -> Array2<TapeRef> {
+    let (lhs, rhs) = (a.map(operand_index), b.map(operand_index));
+    let (a_values, b_values) = (a.values(), b.values());
+    let tape_id = current_tape_id();
+
+    with_tape(|tape: &mut TapeData<F>| {
+        Array2::from_shape_fn(values.dim(), |(i, j)| {
+            let value = values[(i, j)];
+            let index = tape.push(value, false);
+            let start = tape.edges.len() as u32;
+
+            for (p, (operand, requires_grad)) in lhs.row(i).iter().enumerate() {
+                if *requires_grad {
+                    tape.edges.push((*operand, b_values[(p, j)]));
+                }
+            }
+            for (p, (operand, requires_grad)) in rhs.column(j).iter().enumerate() {
+                if *requires_grad {
+                    tape.edges.push((*operand, a_values[(i, p)]));
+                }
+            }
+
+            let node = &mut tape.nodes[index as usize];
+            node.edges = (start, tape.edges.len() as u32);
+            node.requires_grad = node.edges.0 != node.edges.1;
+            check_value(index as usize, value);
+
+            TapeRef { tape_id, index }
+        })
+    })
+}
+
+fn operand_index<F: Float>(operand: &Value<F>) -> (u32, bool) {
+    if let Some(tape_ref) = operand.tape_ref() {
+        check_tape_id(tape_ref);
+        return (tape_ref.index, requires_grad::<F>(tape_ref));
+    }
+
+    let requires_grad = operand.should_compute_grad();
+    let (value, id) = (operand.value(), operand.id());
+    let index = with_tape(|tape: &mut TapeData<F>| {
+        if let Some(index) = tape.external_ids.get(&id) {
+            return *index;
+        }
+
+        let index = tape.push(value, requires_grad);
+        tape.external_ids.insert(id, index);
+        tape.externals.push((index, operand.clone()));
+        index
+    });
+
+    (index, requires_grad)
+}
+
+/// Sweeps the tape backwards from `root` once, then hands the gradients
+/// that reached graph values over to the graph engine.
+pub(crate) fn backward<F: Float>(root: TapeRef) {
+    check_tape_id(root);
+
+    let (externals, seeds) = with_tape(|tape: &mut TapeData<F>| {
+        let root = root.index as usize;
+        let mut grads = vec![F::zero(); root + 1];
+        grads[root] = F::one();
+
+        for idx in (0..=root).rev() {
+            let grad = grads[idx];
+            if grad.is_zero() {
+                continue;
+            }
+
+            let (start, end) = tape.nodes[idx].edges;
+            for (operand, partial) in &tape.edges[start as usize..end as usize] {
+                let operand_grad = grad * *partial;
+                check_grad(idx, operand_grad);
+                grads[*operand as usize] += operand_grad;
+            }
+        }
+
+        tape.externals
+            .iter()
+            .filter(|(idx, value)| {
+                let idx = *idx as usize;
+                idx <= root && !grads[idx].is_zero() && value.should_compute_grad()
+            })
+            .map(|(idx, value)| (value.clone(), Grad::Raw(grads[*idx as usize])))
+            .unzip::<_, _, Vec<Value<F>>, Vec<Grad<F>>>()
+    });
+
+    if externals.is_empty() {
+        return;
+    }
+
+    let (topo_order, grads) = engine::execute_from(&externals, seeds);
+    Value::apply_grads(topo_order, grads);
+}
+
+fn check_value<F: Float>(idx: usize, value: F) {
+    if value.is_finite() {
+        return;
+    }
+
+    if is_anomaly_enabled() {
+        panic!("Anomaly detected: MatMul produced {value} at tape node {idx}");
+    }
+    if value.is_nan() && nan_policy() == NanPolicy::Panic {
+        panic!("Value cannot be NaN: MatMul produced NaN at tape node {idx}");
+    }
+}
+
+fn check_grad<F: Float>(idx: usize, grad: F) {
+    if grad.is_finite() {
+        return;
+    }
+
+    if is_anomaly_enabled() {
+        panic!("Anomaly detected: backward of tape node {idx} produced {grad}");
+    }
+    if grad.is_nan() && nan_policy() == NanPolicy::Panic {
+        panic!("Gradient cannot be NaN: backward of tape node {idx} produced NaN");
+    }
+}
@@ -13,10 +13,10 @@ impl<D> CrossEntropy<D, D>
 where
     D: Dimension + RemoveAxis,
 {
-    fn with_class_probabilities(
-        probabilities: &Tensor<D>,
-        class_probabilities: &Tensor<D>,
-    ) -> Tensor<D> {
+    fn with_class_probabilities<F: Float>(
+        probabilities: &Tensor<D, F>,
+        class_probabilities: &Tensor<D, F>,
+    ) -> Tensor<D, F> {
         let mut dim = class_probabilities.raw_dim();
         dim[class_probabilities.ndim() - 1] = 1;
 
@@ -46,15 +46,15 @@ where
         Tensor::from_shape_vec(dim, cross_entropy).unwrap()
     }
 
-    fn normalized_with_prob<E: Dimension>(
-        probabilities: &Tensor<E>,
-        class_probabilities: &Tensor<E>,
-    ) -> Value {
+    fn normalized_with_prob<E: Dimension, F: Float>(
+        probabilities: &Tensor<E, F>,
+        class_probabilities: &Tensor<E, F>,
+    ) -> Value<F> {
         probabilities
             .iter()
             .zip(class_probabilities)
             .map(|(p, class_p)| &(-(p).log()) * class_p)
-            .sum::<Value>()
+            .sum::<Value<F>>()
     }
 }
 
@@ -63,12 +63,15 @@ where
     D: Dimension<Smaller = E> + RemoveAxis,
     E: Dimension<Larger = D>,
 {
-    fn with_class_indices(probabilities: &Tensor<D>, class_indices: &Tensor<E>) -> Tensor<E> {
+    fn with_class_indices<F: Float>(
+        probabilities: &Tensor<D, F>,
+        class_indices: &Tensor<E, F>,
+    ) -> Tensor<E, F> {
         let indices = class_indices.clone().into_raw_vec();
 
         let cross_entropy = match probabilities.ndim() {
             1 => {
-                let index = indices[0].value() as usize;
+                let index = indices[0].value().as_f64() as usize;
                 let probabilities = probabilities.clone().into_raw_vec();
 
                 vec![-probabilities[index].log()]
@@ -77,7 +80,7 @@ where
                 let mut normalized = vec![];
 
                 for (i, batch_probs) in probabilities.outer_iter().enumerate() {
-                    let index = indices[i].value() as usize;
+                    let index = indices[i].value().as_f64() as usize;
                     let single_probs = batch_probs.to_owned();
 
                     let normalized_batch = -single_probs.into_raw_vec()[index].log();
@@ -95,7 +98,7 @@ where
 macro_rules! impl_criterion_for_class_indices {
     [$(($predicted_dim: ident, $target_dim: ident)),*] => {
         $(impl Criterion<$predicted_dim, $target_dim> for CrossEntropy<$predicted_dim, $target_dim> {
-            fn loss(reduction: Reduction, predicted: &Tensor<$predicted_dim>, target: &Tensor<$target_dim>) -> Value {
+            fn loss<F: Float>(reduction: Reduction, predicted: &Tensor<$predicted_dim, F>, target: &Tensor<$target_dim, F>) -> Value<F> {
                 let n = predicted.ndim();
                 let probabilities = Activation::Softmax(n-1).forward(predicted);
                 let cross_entropy = Self::with_class_indices(&probabilities, target);
@@ -110,7 +113,7 @@ impl_criterion_for_class_indices![(Ix1, Ix0), (Ix2, Ix1), (Ix3, Ix2)];
 macro_rules! impl_criterion_for_class_probabilites {
     [$($dim: ident),*] => {
         $(impl Criterion<$dim, $dim> for CrossEntropy<$dim, $dim> {
-            fn loss(reduction: Reduction, predicted: &Tensor<$dim>, target: &Tensor<$dim>) -> Value {
+            fn loss<F: Float>(reduction: Reduction, predicted: &Tensor<$dim, F>, target: &Tensor<$dim, F>) -> Value<F> {
                 let n = predicted.ndim();
                 let probabilities = Activation::Softmax(n-1).forward(predicted);
                 let cross_entropy = Self::with_class_probabilities(&probabilities, target);
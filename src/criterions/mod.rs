@@ -3,6 +3,7 @@ mod mse;
 
 use crate::tensor::Tensor;
 use crate::value::Value;
+use crate::Float;
 use ndarray::Dimension;
 
 pub use cross_entropy::CrossEntropy;
@@ -14,12 +15,16 @@ pub enum Reduction {
 }
 
 pub trait Criterion<D: Dimension, E: Dimension> {
-    fn loss(reduction: Reduction, predicted: &Tensor<D>, target: &Tensor<E>) -> Value;
+    fn loss<F: Float>(
+        reduction: Reduction,
+        predicted: &Tensor<D, F>,
+        target: &Tensor<E, F>,
+    ) -> Value<F>;
 
-    fn reduce(reduction: Reduction, loss: &Tensor<E>) -> Value {
+    fn reduce<F: Float>(reduction: Reduction, loss: &Tensor<E, F>) -> Value<F> {
         match reduction {
             Reduction::Mean => {
-                let n = loss.len() as f64;
+                let n = F::cast(loss.len() as f64);
                 loss.sum() / n
             }
             Reduction::Sum => loss.sum(),
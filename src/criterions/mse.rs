@@ -4,15 +4,16 @@ use crate::prelude::*;
 pub struct MSE;
 
 impl MSE {
-    fn mse<D>(predicted: &Tensor<D>, target: &Tensor<D>) -> Tensor<D>
+    fn mse<D, F>(predicted: &Tensor<D, F>, target: &Tensor<D, F>) -> Tensor<D, F>
     where
         D: Dimension,
+        F: Float,
     {
         let dim = predicted.raw_dim();
 
         let mut mse = vec![];
         for (pred, actual) in predicted.into_iter().zip(target) {
-            mse.push((pred - actual).powf(2.0));
+            mse.push((pred - actual).powf(F::cast(2.0)));
         }
 
         Tensor::from_shape_vec(dim, mse).unwrap()
@@ -23,7 +24,11 @@ impl<D> Criterion<D, D> for MSE
 where
     D: Dimension,
 {
-    fn loss(reduction: Reduction, predicted: &Tensor<D>, target: &Tensor<D>) -> Value {
+    fn loss<F: Float>(
+        reduction: Reduction,
+        predicted: &Tensor<D, F>,
+        target: &Tensor<D, F>,
+    ) -> Value<F> {
         let mse = Self::mse(predicted, target);
         Self::reduce(reduction, &mse)
     }
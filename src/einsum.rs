@@ -0,0 +1,290 @@
+use std::iter::zip;
+
+use indexmap::IndexMap;
+use ndarray::Data;
+
+use crate::float::Float;
+use crate::prelude::*;
+use crate::tensor::matmul;
+
+/// A `Value` tensor of any dimensionality that can be passed to [`einsum`].
+pub trait EinsumOperand<F: Float> {
+    fn view_dyn(&self) -> ArrayViewD<Value<F>>;
+}
+
+impl<S, D, F> EinsumOperand<F> for ArrayBase<S, D>
+where
+    S: Data<Elem = Value<F>>,
+    D: Dimension,
+    F: Float,
+{
+    fn view_dyn(&self) -> ArrayViewD<Value<F>> {
+        self.view().into_dyn()
+    }
+}
+
+/// Einstein summation over `Value` tensors, e.g. `einsum("bij,bjk->bik",
+/// &[&a, &b])` for a batched matrix product. Without `->`, the output holds
+/// every subscript that appears exactly once, in alphabetical order.
+///
+/// Two operands that contract at least one subscript become a (batched)
+/// matrix product on the fused matmul path; everything else, including
+/// diagonals such as `"ii->i"`, is built from one product per term and one
+/// sum per output element. Operands may have different dimensionality, as in
+/// `einsum("i,ij,j->", &[&x, &w, &y])`.
+pub fn einsum<E, F>(subscripts: &str, operands: &[&dyn EinsumOperand<F>]) -> Tensor<E, F>
+where
+    E: Dimension,
+    F: Float,
+{
+    let operands = operands
+        .iter()
+        .map(|operand| operand.view_dyn())
+        .collect::<Vec<ArrayViewD<Value<F>>>>();
+    let spec = Spec::parse(subscripts, &operands);
+
+    let output = match operands.as_slice() {
+        [a, b] => spec.fused(a, b),
+        _ => None,
+    };
+    let output = output.unwrap_or_else(|| spec.contract(&operands));
+
+    let ndim = output.ndim();
+    output.into_dimensionality().unwrap_or_else(|_| {
+        panic!("Einsum \"{subscripts}\" produces {ndim} axes, which does not match the requested dimension")
+    })
+}
+
+struct Spec {
+    inputs: Vec<Vec<char>>,
+    output: Vec<char>,
+    // Every subscript in order of first appearance, with the length of its axes.
+    sizes: IndexMap<char, usize>,
+}
+
+impl Spec {
+    fn parse<F: Float>(subscripts: &str, operands: &[ArrayViewD<Value<F>>]) -> Self {
+        let invalid = |reason: String| -> ! {
+            panic!("Invalid einsum subscripts \"{subscripts}\": {reason}")
+        };
+
+        let compact = subscripts
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>();
+        if compact.contains("...") {
+            invalid(String::from("ellipsis is not supported"));
+        }
+
+        let mut sides = compact.split("->");
+        let inputs = sides.next().unwrap_or_default();
+        let output = sides.next();
+        if sides.next().is_some() {
+            invalid(String::from("expected at most one \"->\""));
+        }
+
+        let inputs = inputs
+            .split(',')
+            .map(|input| input.chars().collect())
+            .collect::<Vec<Vec<char>>>();
+        if inputs.len() != operands.len() {
+            invalid(format!(
+                "{} input subscripts were given for {} operands",
+                inputs.len(),
+                operands.len()
+            ));
+        }
+
+        let mut sizes = IndexMap::new();
+        for (idx, (labels, operand)) in zip(&inputs, operands).enumerate() {
+            if labels.len() != operand.ndim() {
+                invalid(format!(
+                    "operand {idx} has {} axes but \"{}\" names {}",
+                    operand.ndim(),
+                    labels.iter().collect::<String>(),
+                    labels.len()
+                ));
+            }
+
+            for (label, size) in zip(labels, operand.shape()) {
+                if !label.is_ascii_alphabetic() {
+                    invalid(format!("'{label}' is not a letter"));
+                }
+
+                let prev = *sizes.entry(*label).or_insert(*size);
+                if prev != *size {
+                    invalid(format!(
+                        "'{label}' has length {size} in operand {idx} but {prev} elsewhere"
+                    ));
+                }
+            }
+        }
+
+        let output = match output {
+            Some(output) => output.chars().collect::<Vec<char>>(),
+            None => {
+                let mut output = sizes
+                    .keys()
+                    .filter(|label| inputs.iter().flatten().filter(|l| l == label).count() == 1)
+                    .copied()
+                    .collect::<Vec<char>>();
+                output.sort_unstable();
+                output
+            }
+        };
+        for (idx, label) in output.iter().enumerate() {
+            if !sizes.contains_key(label) {
+                invalid(format!("output '{label}' does not appear in any input"));
+            }
+            if output[..idx].contains(label) {
+                invalid(format!("output '{label}' appears more than once"));
+            }
+        }
+
+        Spec {
+            inputs,
+            output,
+            sizes,
+        }
+    }
+
+    fn len(&self, labels: &[char]) -> usize {
+        labels.iter().map(|label| self.sizes[label]).product()
+    }
+
+    // Sums the product of every operand over each assignment of the
+    // subscripts missing from the output.
+    fn contract<F: Float>(&self, operands: &[ArrayViewD<Value<F>>]) -> Tensor<IxDyn, F> {
+        let summed = self
+            .sizes
+            .keys()
+            .filter(|label| !self.output.contains(label))
+            .copied()
+            .collect::<Vec<char>>();
+
+        let positions = |labels: &[char]| {
+            labels
+                .iter()
+                .map(|label| self.sizes.get_index_of(label).unwrap())
+                .collect::<Vec<usize>>()
+        };
+        let (output_positions, summed_positions) = (positions(&self.output), positions(&summed));
+        let input_positions = self
+            .inputs
+            .iter()
+            .map(|labels| positions(labels))
+            .collect::<Vec<Vec<usize>>>();
+
+        let shape =
+            |labels: &[char]| IxDyn(&labels.iter().map(|l| self.sizes[l]).collect::<Vec<_>>());
+        let summed_shape = shape(&summed);
+        let mut assignment = vec![0; self.sizes.len()];
+
+        Tensor::from_shape_fn(shape(&self.output), |idx| {
+            for (position, i) in zip(&output_positions, idx.slice()) {
+                assignment[*position] = *i;
+            }
+
+            ndarray::indices(summed_shape.clone())
+                .into_iter()
+                .map(|summed_idx| {
+                    for (position, i) in zip(&summed_positions, summed_idx.slice()) {
+                        assignment[*position] = *i;
+                    }
+
+                    zip(operands, &input_positions)
+                        .map(|(operand, positions)| {
+                            let coords =
+                                positions.iter().map(|p| assignment[*p]).collect::<Vec<_>>();
+                            operand[IxDyn(&coords)].clone()
+                        })
+                        .reduce(|product, x| product * x)
+                        .unwrap()
+                })
+                .sum()
+        })
+    }
+
+    // Splits the subscripts of two operands into batch, row, contracted and
+    // column groups, as long as each one lines up with a matrix product.
+    fn fused<F: Float>(
+        &self,
+        a: &ArrayViewD<Value<F>>,
+        b: &ArrayViewD<Value<F>>,
+    ) -> Option<Tensor<IxDyn, F>> {
+        let (lhs, rhs, output) = (&self.inputs[0], &self.inputs[1], &self.output);
+        let has_repeats =
+            |labels: &[char]| (0..labels.len()).any(|idx| labels[..idx].contains(&labels[idx]));
+        let shared = |label: &char| lhs.contains(label) && rhs.contains(label);
+        let summed_alone = lhs
+            .iter()
+            .chain(rhs)
+            .any(|label| !(output.contains(label) || shared(label)));
+        if has_repeats(lhs) || has_repeats(rhs) || summed_alone {
+            return None;
+        }
+
+        let filter = |labels: &[char], keep: &dyn Fn(&char) -> bool| {
+            labels
+                .iter()
+                .filter(|l| keep(l))
+                .copied()
+                .collect::<Vec<char>>()
+        };
+        let batch = filter(output, &|l| lhs.contains(l) && rhs.contains(l));
+        let rows = filter(output, &|l| lhs.contains(l) && !rhs.contains(l));
+        let cols = filter(output, &|l| rhs.contains(l) && !lhs.contains(l));
+        let contracted = filter(lhs, &|l| !output.contains(l));
+        if contracted.is_empty() {
+            return None;
+        }
+
+        let (batches, m, k, n) = (
+            self.len(&batch),
+            self.len(&rows),
+            self.len(&contracted),
+            self.len(&cols),
+        );
+        let a = permute(a, lhs, &[&batch, &rows, &contracted]);
+        let a = a.to_shape((batches, m, k)).unwrap();
+        let b = permute(b, rhs, &[&batch, &contracted, &cols]);
+        let b = b.to_shape((batches, k, n)).unwrap();
+
+        let mut products = Vec::with_capacity(batches * m * n);
+        for idx in 0..batches {
+            products.extend(matmul(
+                a.index_axis(Axis(0), idx),
+                b.index_axis(Axis(0), idx),
+            ));
+        }
+
+        let labels = [batch, rows, cols].concat();
+        let shape = labels.iter().map(|l| self.sizes[l]).collect::<Vec<usize>>();
+        let product = Tensor::from_shape_vec(IxDyn(&shape), products).unwrap();
+
+        let axes = output
+            .iter()
+            .map(|label| labels.iter().position(|l| l == label).unwrap())
+            .collect::<Vec<usize>>();
+        Some(
+            product
+                .permuted_axes(axes)
+                .as_standard_layout()
+                .into_owned(),
+        )
+    }
+}
+
+fn permute<'a, F: Float>(
+    operand: &ArrayViewD<'a, Value<F>>,
+    labels: &[char],
+    groups: &[&[char]],
+) -> ArrayViewD<'a, Value<F>> {
+    let axes = groups
+        .concat()
+        .iter()
+        .map(|label| labels.iter().position(|l| l == label).unwrap())
+        .collect::<Vec<usize>>();
+
+    operand.clone().permuted_axes(axes)
+}
@@ -0,0 +1,77 @@
+use crate::shared::MaybeSync;
+use ndarray::ScalarOperand;
+use num_traits::FromPrimitive;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::{Debug, Display};
+use std::iter::Sum;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+/// Floating point precision a `Value` is stored and differentiated in.
+pub trait Float:
+    num_traits::Float
+    + FromPrimitive
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+    + Sum
+    + Default
+    + Debug
+    + Display
+    + ScalarOperand
+    + Serialize
+    + DeserializeOwned
+    + MaybeSync
+    + 'static
+{
+    fn cast(value: f64) -> Self;
+
+    fn as_f64(self) -> f64;
+}
+
+impl Float for f32 {
+    fn cast(value: f64) -> Self {
+        value as f32
+    }
+
+    fn as_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl Float for f64 {
+    fn cast(value: f64) -> Self {
+        value
+    }
+
+    fn as_f64(self) -> f64 {
+        self
+    }
+}
+
+/// Plain numbers that can be combined with a `Value<F>`: the float itself
+/// and, as before `Value` became generic, any type that converts into it.
+/// `i32` is accepted by both precisions so integer literals work with either.
+pub trait Scalar<F: Float>: Copy {
+    fn into_float(self) -> F;
+}
+
+impl<F: Float> Scalar<F> for F {
+    fn into_float(self) -> F {
+        self
+    }
+}
+
+macro_rules! impl_scalar {
+    ($float: ty, [$($scalar: ty),*]) => {
+        $(impl Scalar<$float> for $scalar {
+            fn into_float(self) -> $float {
+                self as $float
+            }
+        })*
+    };
+}
+
+impl_scalar!(f64, [f32, i8, i16, i32, u8, u16, u32]);
+impl_scalar!(f32, [i8, i16, i32, u8, u16]);
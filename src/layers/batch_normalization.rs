@@ -1,112 +1,68 @@
-use std::cell::Cell;
-
 use ndarray::RemoveAxis;
 
+use crate::shared::Lock;
 use crate::{prelude::*, Layer};
 
-pub struct BatchNorm {
+pub struct BatchNorm<F: Float = f64> {
     pub name: String,
     pub features: usize,
-    pub eps: f64,
-    pub momentum: f64,
-    weight: Tensor<Ix1>,
-    bias: Tensor<Ix1>,
-    running_mean: Cell<Tensor<Ix1>>,
-    running_var: Cell<Tensor<Ix1>>,
+    pub eps: F,
+    pub momentum: F,
+    weight: Tensor<Ix1, F>,
+    bias: Tensor<Ix1, F>,
+    running_mean: Lock<Array1<F>>,
+    running_var: Lock<Array1<F>>,
 }
 
-impl Default for BatchNorm {
+impl<F: Float> Default for BatchNorm<F> {
     fn default() -> Self {
         BatchNorm {
             name: "Batch Normalization".into(),
             features: 0,
-            eps: 1e-5,
-            momentum: 0.1,
+            eps: F::cast(1e-5),
+            momentum: F::cast(0.1),
             weight: Tensor::ones(0),
             bias: Tensor::zeros(0),
-            running_mean: Cell::new(Tensor::ones(0)),
-            running_var: Cell::new(Tensor::ones(0)),
+            running_mean: Lock::new(Array1::ones(0)),
+            running_var: Lock::new(Array1::ones(0)),
         }
     }
 }
 
-impl BatchNorm {
+impl<F: Float> BatchNorm<F> {
     pub fn new(name: impl ToString, features: usize) -> Self {
         Self {
             name: name.to_string(),
             features,
             weight: Tensor::ones(features),
             bias: Tensor::zeros(features),
-            running_mean: Cell::new(Tensor::zeros(features)),
-            running_var: Cell::new(Tensor::ones(features)),
+            running_mean: Lock::new(Array1::zeros(features)),
+            running_var: Lock::new(Array1::ones(features)),
             ..Default::default()
         }
     }
 
+    // Running statistics are plain values so they never keep the graph (or a
+    // tape) of the batch they were computed from alive.
     fn update_running_stat<D: Dimension>(
         &self,
-        celled_running_stat: &Cell<Tensor<Ix1>>,
-        batch_stat: &Tensor<D>,
+        celled_running_stat: &Lock<Array1<F>>,
+        batch_stat: &Tensor<D, F>,
     ) {
-        let scaled_batch_stat = (batch_stat * self.momentum)
-            .into_shape(batch_stat.len())
-            .unwrap();
-
-        let running_stat = celled_running_stat.take();
-        let scaled_running_stat = &running_stat * (1. - self.momentum);
-
-        let new_running_stat = scaled_batch_stat + scaled_running_stat;
-        celled_running_stat.set(new_running_stat);
-    }
+        let batch_stat = batch_stat.values().into_shape(batch_stat.len()).unwrap();
 
-    fn iterative_mean<D: Dimension + RemoveAxis>(
-        &self,
-        input: &Tensor<D>,
-        axes: &[usize],
-    ) -> Tensor<D>
-    where
-        D::Smaller: Dimension<Larger = D>,
-    {
-        axes.iter()
-            .fold(input.clone(), |meaned, axis| self.mean::<D>(&meaned, *axis))
+        let mut running_stat = celled_running_stat.borrow_mut();
+        running_stat.zip_mut_with(&batch_stat, |running, &batch| {
+            *running = batch * self.momentum + *running * (F::one() - self.momentum);
+        });
     }
 
-    fn mean<D: Dimension + RemoveAxis>(&self, input: &Tensor<D>, axis: usize) -> Tensor<D>
-    where
-        D::Smaller: Dimension<Larger = D>,
-    {
-        let mean = input.mean_axis(Axis(axis)).unwrap();
-        mean.insert_axis(Axis(axis))
-    }
-
-    fn iterative_var<D: Dimension + RemoveAxis>(
-        &self,
-        input: &Tensor<D>,
-        axes: &[usize],
-        corrected: bool,
-    ) -> Tensor<D>
-    where
-        D::Smaller: Dimension<Larger = D>,
-    {
-        let shape = input.shape();
-        let biased = axes.iter().map(|axis| shape[*axis] as f64).product::<f64>();
-
-        let mean = self.iterative_mean(input, axes);
-        let normalized = (input - mean).mapv(|v| v.powf(2.0));
-
-        let var = self.iterative_mean(&normalized, axes);
-        match corrected {
-            true => (var * biased) / (biased - 1.0),
-            false => var,
-        }
-    }
-
-    fn reshape<D: Dimension>(&self, parameter: &Tensor<Ix1>, shape: &[usize]) -> Tensor<D> {
+    fn reshape<D: Dimension>(&self, parameter: &Tensor<Ix1, F>, shape: &[usize]) -> Tensor<D, F> {
         let reshaped = parameter.clone().into_shape(shape).unwrap();
         reshaped.into_dimensionality::<D>().unwrap()
     }
 
-    fn transform<D: Dimension>(&self, output: Tensor<D>) -> Tensor<D> {
+    fn transform<D: Dimension>(&self, output: Tensor<D, F>) -> Tensor<D, F> {
         let mut reshape = vec![1; output.ndim()];
         reshape[0] = self.features;
 
@@ -117,29 +73,29 @@ impl BatchNorm {
     }
 }
 
-impl<D> Layer<D, D> for BatchNorm
+impl<D, F> Layer<D, D, F> for BatchNorm<F>
 where
     D: Dimension + RemoveAxis,
     D::Smaller: Dimension<Larger = D>,
+    F: Float,
 {
-    fn parameters(&self) -> Tensor<Ix1> {
+    fn parameters(&self) -> Tensor<Ix1, F> {
         let mut parameters = self.weight.clone().into_raw_vec();
         parameters.append(&mut self.bias.clone().into_raw_vec());
 
         Tensor::from_vec(parameters)
     }
 
-    fn forward(&self, input: &Tensor<D>) -> Tensor<D> {
+    fn forward(&self, input: &Tensor<D, F>) -> Tensor<D, F> {
         let n = input.ndim();
 
-        let mut single_channel_mean_dims = (2..n).collect::<Vec<usize>>();
-        let mut stat_dims = vec![0];
-        stat_dims.append(&mut single_channel_mean_dims);
+        let mut stat_axes = vec![Axis(0)];
+        stat_axes.extend((2..n).map(Axis));
 
-        let mean = self.iterative_mean(input, &stat_dims);
+        let mean: Tensor<D, F> = input.mean_axes(&stat_axes, true);
 
-        let unbiased_var = self.iterative_var(input, &stat_dims, false);
-        let biased_var = self.iterative_var(input, &stat_dims, true);
+        let unbiased_var: Tensor<D, F> = input.var_axes(&stat_axes, 0, true);
+        let biased_var: Tensor<D, F> = input.var_axes(&stat_axes, 1, true);
 
         let shifted_var = unbiased_var.mapv(|v| (v + self.eps).sqrt());
 
@@ -152,11 +108,25 @@ where
         output
     }
 
-    fn biases(&self) -> Tensor<Ix1> {
+    fn biases(&self) -> Tensor<Ix1, F> {
         self.bias.clone()
     }
 
-    fn weights(&self) -> Tensor<Ix1> {
+    fn buffers(&self) -> Vec<F> {
+        let running_mean = self.running_mean.borrow().to_vec();
+        let running_var = self.running_var.borrow().to_vec();
+
+        [running_mean, running_var].concat()
+    }
+
+    fn set_buffers(&self, buffers: &[F]) {
+        let (running_mean, running_var) = buffers.split_at(self.features);
+
+        *self.running_mean.borrow_mut() = Array1::from_vec(running_mean.to_vec());
+        *self.running_var.borrow_mut() = Array1::from_vec(running_var.to_vec());
+    }
+
+    fn weights(&self) -> Tensor<Ix1, F> {
         self.weight.clone()
     }
 
@@ -182,14 +152,10 @@ mod tests {
         let batch_norm = BatchNorm::new("bn", features);
         batch_norm.forward(input);
 
-        let running_mean = batch_norm.running_mean.take().into_raw_vec();
-        let running_var = batch_norm.running_var.take().into_raw_vec();
-
-        let mut outputs = vec![];
-        outputs.extend(running_mean);
-        outputs.extend(running_var);
+        let running_mean = batch_norm.running_mean.borrow().to_vec();
+        let running_var = batch_norm.running_var.borrow().to_vec();
 
-        outputs.into_iter().map(|v| v.value()).collect::<Vec<f64>>()
+        [running_mean, running_var].concat()
     }
 
     #[test]
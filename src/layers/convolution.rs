@@ -7,7 +7,7 @@ pub type Conv1D = Convolution<Ix1, Ix3>;
 pub type Conv2D = Convolution<Ix2, Ix4>;
 pub type Conv3D = Convolution<Ix3, Ix5>;
 
-pub struct Convolution<SingleChannelDim: Dimension, BatchedDim: Dimension> {
+pub struct Convolution<SingleChannelDim: Dimension, BatchedDim: Dimension, F: Float = f64> {
     pub name: String,
     pub in_channels: usize,
     pub out_channels: usize,
@@ -17,15 +17,16 @@ pub struct Convolution<SingleChannelDim: Dimension, BatchedDim: Dimension> {
     pub stride: SingleChannelDim,
     pub dilation: SingleChannelDim,
 
-    pub weights: Tensor<BatchedDim>,
-    pub biases: Tensor<Ix1>,
+    pub weights: Tensor<BatchedDim, F>,
+    pub biases: Tensor<Ix1, F>,
 }
 
-impl<SingleChannelDim, MultiChannelDim, BatchedDim> Convolution<SingleChannelDim, BatchedDim>
+impl<SingleChannelDim, MultiChannelDim, BatchedDim, F> Convolution<SingleChannelDim, BatchedDim, F>
 where
     SingleChannelDim: Dimension<Larger = MultiChannelDim> + RemoveAxis,
     MultiChannelDim: Dimension<Smaller = SingleChannelDim, Larger = BatchedDim> + RemoveAxis,
     BatchedDim: Dimension<Smaller = MultiChannelDim> + RemoveAxis,
+    F: Float,
 {
     pub fn new<J: IntoDimension<Dim = SingleChannelDim> + Clone>(
         name: impl ToString,
@@ -69,7 +70,7 @@ where
         }
     }
 
-    fn pad_input(&self, input: &Tensor<MultiChannelDim>) -> Tensor<MultiChannelDim> {
+    fn pad_input(&self, input: &Tensor<MultiChannelDim, F>) -> Tensor<MultiChannelDim, F> {
         let mut padded_input = input.clone();
 
         for (axis, &padding) in self.padding.slice().iter().enumerate() {
@@ -78,8 +79,11 @@ where
 
             let total_padding = padding_dim.slice().iter().product();
 
-            let padding_tensor: Tensor<MultiChannelDim> =
-                Tensor::from_shape_vec(padding_dim, vec![val!(0.0); total_padding]).unwrap();
+            let padding_tensor: Tensor<MultiChannelDim, F> = Tensor::from_shape_vec(
+                padding_dim,
+                vec![Value::constant(F::zero()); total_padding],
+            )
+            .unwrap();
             padded_input = concatenate![Axis(axis + 1), padding_tensor, padded_input];
             padded_input = concatenate![Axis(axis + 1), padded_input, padding_tensor];
         }
@@ -118,7 +122,7 @@ where
         channeled_size
     }
 
-    fn dilate_filter(&self, filter: &Tensor<MultiChannelDim>) -> Tensor<MultiChannelDim> {
+    fn dilate_filter(&self, filter: &Tensor<MultiChannelDim, F>) -> Tensor<MultiChannelDim, F> {
         let dilation = self.dilation.insert_axis(Axis(0));
         let dilated_filter =
             filter.slice_each_axis(|ax| Slice::new(0, None, dilation[ax.axis.index()] as isize));
@@ -126,7 +130,7 @@ where
         dilated_filter.to_owned()
     }
 
-    pub fn convolve(&self, input: &Tensor<MultiChannelDim>) -> Tensor<MultiChannelDim> {
+    pub fn convolve(&self, input: &Tensor<MultiChannelDim, F>) -> Tensor<MultiChannelDim, F> {
         let input_dim = input.raw_dim();
         let output_shape = self.output_shape(&input_dim);
 
@@ -134,31 +138,40 @@ where
         let mut stride = self.stride.insert_axis(Axis(0));
         stride[0] = self.in_channels;
 
-        let mut output = vec![];
         let input = self.pad_input(&input.to_owned());
 
-        for (weight, bias) in self.weights.outer_iter().zip(self.biases.clone()) {
-            for window in input.windows_with_stride(window_shape.clone(), stride.clone()) {
-                let window = self.dilate_filter(&window.to_owned());
-
-                let convolved = &window * weight.to_owned();
-                output.push(convolved.sum() + bias.to_owned());
-            }
-        }
-
-        Tensor::from_shape_vec(output_shape, output).unwrap()
+        // Every dilated window becomes a row, so the whole convolution is a
+        // single product with the flattened filters.
+        let windows = input
+            .windows_with_stride(window_shape.clone(), stride.clone())
+            .into_iter()
+            .flat_map(|window| self.dilate_filter(&window.to_owned()))
+            .collect::<Vec<Value<F>>>();
+        let patch_size = self.weights.len() / self.out_channels;
+        let windows =
+            Tensor::from_shape_vec((windows.len() / patch_size, patch_size), windows).unwrap();
+        let weights = self
+            .weights
+            .to_shape((self.out_channels, patch_size))
+            .unwrap();
+
+        let convolved = weights.view().dot(&windows.t());
+        let output = convolved + self.biases.clone().insert_axis(Axis(1));
+
+        output.into_shape(output_shape).unwrap()
     }
 }
 
-impl<SingleChannelDim, MultiChannelDim, BatchedDim> Layer<BatchedDim, BatchedDim>
-    for Convolution<SingleChannelDim, BatchedDim>
+impl<SingleChannelDim, MultiChannelDim, BatchedDim, F> Layer<BatchedDim, BatchedDim, F>
+    for Convolution<SingleChannelDim, BatchedDim, F>
 where
     SingleChannelDim: Dimension<Larger = MultiChannelDim> + RemoveAxis,
     MultiChannelDim: Dimension<Smaller = SingleChannelDim, Larger = BatchedDim> + RemoveAxis,
     BatchedDim: Dimension<Smaller = MultiChannelDim> + RemoveAxis,
+    F: Float,
 {
-    fn forward(&self, input: &Tensor<BatchedDim>) -> Tensor<BatchedDim> {
-        let mut convolved_batches: Option<Tensor<BatchedDim>> = None;
+    fn forward(&self, input: &Tensor<BatchedDim, F>) -> Tensor<BatchedDim, F> {
+        let mut convolved_batches: Option<Tensor<BatchedDim, F>> = None;
 
         for single_batch in input.outer_iter() {
             let convolved_single_batch = self.convolve(&single_batch.to_owned());
@@ -175,11 +188,11 @@ where
         convolved_batches.unwrap()
     }
 
-    fn weights(&self) -> Tensor<Ix1> {
+    fn weights(&self) -> Tensor<Ix1, F> {
         self.weights.clone().into_shape(self.weights.len()).unwrap()
     }
 
-    fn biases(&self) -> Tensor<Ix1> {
+    fn biases(&self) -> Tensor<Ix1, F> {
         self.biases.clone().into_shape(self.biases.len()).unwrap()
     }
 
@@ -253,7 +266,7 @@ mod tests {
         let (n, m) = (2, 3);
         let (padding, dilation, kernel_size, stride) = ((2, 2), (1, 1), (n, m), (1, 2));
 
-        let conv2d = Conv2D::new(
+        let conv2d: Conv2D = Conv2D::new(
             "conv2d",
             in_channels,
             out_channels,
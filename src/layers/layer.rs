@@ -1,39 +1,56 @@
 use crate::prelude::*;
+use crate::shared::MaybeSync;
+use crate::tensor_value::TensorValue;
 
-pub trait Layer<In, Out>
+pub trait Layer<In, Out, F = f64>: MaybeSync
 where
     In: Dimension,
     Out: Dimension,
+    F: Float,
 {
-    fn forward(&self, input: &Tensor<In>) -> Tensor<Out>;
+    fn forward(&self, input: &Tensor<In, F>) -> Tensor<Out, F>;
 
-    fn parameters(&self) -> Tensor<Ix1> {
+    /// Tensor-level forward. Layers without one run `forward` on the scalar
+    /// graph and record it as a single node.
+    fn forward_value(&self, input: &TensorValue<In, F>) -> TensorValue<Out, F> {
+        input.apply(|input| self.forward(input))
+    }
+
+    fn parameters(&self) -> Tensor<Ix1, F> {
         let mut params = self.weights().into_raw_vec();
         params.append(&mut self.biases().into_raw_vec());
 
         Tensor::from_vec(params)
     }
 
-    fn weights(&self) -> Tensor<Ix1> {
+    fn weights(&self) -> Tensor<Ix1, F> {
         Tensor::from_vec(vec![])
     }
 
-    fn biases(&self) -> Tensor<Ix1> {
+    fn biases(&self) -> Tensor<Ix1, F> {
         Tensor::from_vec(vec![])
     }
 
-    fn set_weights(&self, new_weights: &[f64]) {
+    fn set_weights(&self, new_weights: &[F]) {
         for (v, &weight) in self.weights().iter().zip(new_weights) {
-            *v.value_mut() = weight.into();
+            *v.value_mut() = weight;
         }
     }
 
-    fn set_biases(&self, new_biases: &[f64]) {
+    fn set_biases(&self, new_biases: &[F]) {
         for (v, &bias) in self.biases().iter().zip(new_biases) {
-            *v.value_mut() = bias.into();
+            *v.value_mut() = bias;
         }
     }
 
+    /// State that `forward` updates without training it, such as running
+    /// statistics.
+    fn buffers(&self) -> Vec<F> {
+        vec![]
+    }
+
+    fn set_buffers(&self, _buffers: &[F]) {}
+
     fn is_trainable(&self) -> bool {
         false
     }
@@ -2,15 +2,16 @@ use std::ops::Add;
 
 use super::Layer;
 use crate::prelude::*;
+use crate::tensor_value::BroadcastInto;
 use crate::utils::{GlorotUniform, WeightInit};
 
-pub struct Linear {
+pub struct Linear<F: Float = f64> {
     pub name: String,
-    pub weights: Tensor<Ix2>,
-    pub biases: Tensor<Ix1>,
+    pub weights: Tensor<Ix2, F>,
+    pub biases: Tensor<Ix1, F>,
 }
 
-impl Linear {
+impl<F: Float> Linear<F> {
     pub fn new(name: impl ToString, nin: usize, nout: usize) -> Self {
         let name = name.to_string();
         let weights =
@@ -25,21 +26,31 @@ impl Linear {
     }
 }
 
-impl<D, E> Layer<D, D> for Linear
+impl<D, E, F> Layer<D, D, F> for Linear<F>
 where
     D: Dimension,
-    Tensor<D>: DotProd<Tensor<Ix2>, Output = Tensor<E>>,
-    Tensor<E>: Add<Tensor<Ix1>, Output = Tensor<D>>,
+    F: Float,
+    Tensor<D, F>: DotProd<Tensor<Ix2, F>, Output = Tensor<E, F>>,
+    Tensor<E, F>: Add<Tensor<Ix1, F>, Output = Tensor<D, F>>,
+    TensorValue<D, F>: DotProd<TensorValue<Ix2, F>, Output = TensorValue<D, F>>,
+    Ix1: BroadcastInto<D>,
 {
-    fn forward(&self, input: &Tensor<D>) -> Tensor<D> {
+    fn forward(&self, input: &Tensor<D, F>) -> Tensor<D, F> {
         input.dot(&self.weights.t().to_owned()) + self.biases.clone()
     }
 
-    fn weights(&self) -> Tensor<Ix1> {
+    fn forward_value(&self, input: &TensorValue<D, F>) -> TensorValue<D, F> {
+        let weights = TensorValue::from_tensor(&self.weights);
+        let biases = TensorValue::from_tensor(&self.biases);
+
+        input.dot(&weights.t()) + biases
+    }
+
+    fn weights(&self) -> Tensor<Ix1, F> {
         self.weights.clone().into_shape(self.weights.len()).unwrap()
     }
 
-    fn biases(&self) -> Tensor<Ix1> {
+    fn biases(&self) -> Tensor<Ix1, F> {
         self.biases.clone()
     }
 
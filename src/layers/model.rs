@@ -2,7 +2,9 @@ use indexmap::IndexMap;
 use serde_pickle::{ser, SerOptions};
 use std::fs::File;
 
-pub trait Model {
+use crate::Float;
+
+pub trait Model<F: Float = f64> {
     fn save_state_dict(&self, path: &str) {
         let mut file = File::create(path).unwrap();
 
@@ -11,7 +13,7 @@ pub trait Model {
         ser::to_writer(&mut file, &state_dict, SerOptions::new()).unwrap();
     }
 
-    fn state_dict(&self) -> IndexMap<String, Vec<f64>>;
+    fn state_dict(&self) -> IndexMap<String, Vec<F>>;
 
     fn load_state_dict(&mut self, path: &str);
 }
@@ -43,8 +43,8 @@ where
         }
     }
 
-    fn avg_pooling(&self, window: Tensor<D>) -> Value {
-        let n = window.len() as f64;
+    fn avg_pooling<F: Float>(&self, window: Tensor<D, F>) -> Value<F> {
+        let n = F::cast(window.len() as f64);
         window.sum() / n
     }
 }
@@ -57,7 +57,7 @@ where
         String::from("AveragePooling")
     }
 
-    fn pool(&self, input: Tensor<D>) -> Tensor<D> {
+    fn pool<F: Float>(&self, input: Tensor<D, F>) -> Tensor<D, F> {
         let mut pooled_input = vec![];
 
         for window in input.windows_with_stride(self.size.clone(), self.stride.clone()) {
@@ -43,7 +43,7 @@ where
         }
     }
 
-    fn max_pooling(&self, window: Tensor<D>) -> Value {
+    fn max_pooling<F: Float>(&self, window: Tensor<D, F>) -> Value<F> {
         window.into_iter().reduce(Value::max).unwrap()
     }
 }
@@ -56,7 +56,7 @@ where
         String::from("MaxPooling")
     }
 
-    fn pool(&self, input: Tensor<D>) -> Tensor<D> {
+    fn pool<F: Float>(&self, input: Tensor<D, F>) -> Tensor<D, F> {
         let mut pooled_input = vec![];
 
         for window in input.windows_with_stride(self.size.clone(), self.stride.clone()) {
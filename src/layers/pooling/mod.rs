@@ -3,7 +3,7 @@ mod max_pool;
 
 use ndarray::{Dimension, RemoveAxis};
 
-use crate::Tensor;
+use crate::{Float, Tensor};
 
 pub use self::avg_pool::AvgPool;
 pub use self::max_pool::MaxPool;
@@ -11,19 +11,20 @@ pub use super::Layer;
 
 pub trait PoolingFn<D: Dimension> {
     fn pool_name(&self) -> String;
-    fn pool(&self, input: Tensor<D>) -> Tensor<D>;
+    fn pool<F: Float>(&self, input: Tensor<D, F>) -> Tensor<D, F>;
     fn output_shape<E: Dimension>(&self, input_dim: &E) -> E;
 }
 
 macro_rules! impl_layer_for_pool {
     [$($pooling: ident),+] => {
-        $(impl<SingleChannelDim, MultiChannelDim, BatchedDim> Layer<BatchedDim, BatchedDim> for $pooling<SingleChannelDim>
+        $(impl<SingleChannelDim, MultiChannelDim, BatchedDim, F> Layer<BatchedDim, BatchedDim, F> for $pooling<SingleChannelDim>
 where
     BatchedDim: Dimension<Smaller = MultiChannelDim> + RemoveAxis,
     MultiChannelDim: Dimension<Larger = BatchedDim, Smaller = SingleChannelDim> + RemoveAxis,
-    SingleChannelDim: Dimension<Larger = MultiChannelDim>
+    SingleChannelDim: Dimension<Larger = MultiChannelDim>,
+    F: Float,
 {
-    fn forward(&self, input: &Tensor<BatchedDim>) -> Tensor<BatchedDim> {
+    fn forward(&self, input: &Tensor<BatchedDim, F>) -> Tensor<BatchedDim, F> {
         let mut output_channels = vec![];
 
         for single_batch in input.outer_iter() {
@@ -1,4 +1,5 @@
 use super::{Layer, Model};
+use crate::autograd::profiler;
 use crate::prelude::*;
 use indexmap::IndexMap;
 use ndarray::RemoveAxis;
@@ -14,35 +15,49 @@ macro_rules! sequential {
         )*
         Sequential::new(layers)
     }};
+    ($d: tt, $f: ty, [$($layer: expr),*]) => {{
+        let mut layers: Vec<Box<dyn Layer<$d, $d, $f>>> = vec![];
+        $(
+            layers.push(Box::new($layer));
+        )*
+        Sequential::new(layers)
+    }};
 }
 
-pub struct Sequential<D> {
-    pub layers: Vec<Box<dyn Layer<D, D>>>,
+pub struct Sequential<D, F: Float = f64> {
+    pub layers: Vec<Box<dyn Layer<D, D, F>>>,
 }
 
-impl<D, E> Sequential<D>
+impl<D, E, F> Sequential<D, F>
 where
     E: Dimension<Smaller = D> + RemoveAxis,
     D: Dimension<Larger = E>,
+    F: Float,
 {
-    pub fn new(layers: Vec<Box<dyn Layer<D, D>>>) -> Self {
+    pub fn new(layers: Vec<Box<dyn Layer<D, D, F>>>) -> Self {
         Sequential { layers }
     }
 
-    pub fn parameters(&self) -> Tensor<Ix1> {
+    pub fn parameters(&self) -> Tensor<Ix1, F> {
         self.layers
             .iter()
             .flat_map(|layer| layer.parameters().to_vec())
             .collect()
     }
 
-    pub fn forward(&self, inputs: &Tensor<D>) -> Tensor<D> {
+    pub fn forward(&self, inputs: &Tensor<D, F>) -> Tensor<D, F> {
+        self.layers.iter().fold(inputs.clone(), |output, layer| {
+            profiler::time_layer(|| layer.name(), || layer.forward(&output))
+        })
+    }
+
+    pub fn forward_value(&self, inputs: &TensorValue<D, F>) -> TensorValue<D, F> {
         self.layers
             .iter()
-            .fold(inputs.clone(), |output, layer| layer.forward(&output))
+            .fold(inputs.clone(), |output, layer| layer.forward_value(&output))
     }
 
-    pub fn forward_batch(&self, batches: &Tensor<E>) -> Tensor<E> {
+    pub fn forward_batch(&self, batches: &Tensor<E, F>) -> Tensor<E, F> {
         let mut outputs = vec![];
         let mut output_size = <D>::default();
 
@@ -60,19 +75,19 @@ where
     }
 }
 
-impl<D: Dimension> Model for Sequential<D> {
-    fn state_dict(&self) -> IndexMap<String, Vec<f64>> {
-        let mut state_dict: IndexMap<String, Vec<f64>> = IndexMap::new();
+impl<D: Dimension, F: Float> Model<F> for Sequential<D, F> {
+    fn state_dict(&self) -> IndexMap<String, Vec<F>> {
+        let mut state_dict: IndexMap<String, Vec<F>> = IndexMap::new();
 
         let trainable_layers = self.layers.iter().filter(|x| x.is_trainable());
 
         for layer in trainable_layers {
             let (weight_key, bias_key) = (layer.name() + ".weight", layer.name() + ".bias");
 
-            let layer_weights = layer.weights().iter().map(|v| v.value()).collect();
+            let layer_weights = layer.weights().values().to_vec();
             state_dict.insert(weight_key, layer_weights);
 
-            let layer_biases = layer.biases().iter().map(|v| v.value()).collect();
+            let layer_biases = layer.biases().values().to_vec();
             state_dict.insert(bias_key, layer_biases);
         }
 
@@ -81,8 +96,7 @@ impl<D: Dimension> Model for Sequential<D> {
 
     fn load_state_dict(&mut self, path: &str) {
         let file = File::open(path).unwrap();
-        let state_dict: IndexMap<String, Vec<f64>> =
-            de::from_reader(file, DeOptions::new()).unwrap();
+        let state_dict: IndexMap<String, Vec<F>> = de::from_reader(file, DeOptions::new()).unwrap();
 
         let trainable_layers = self.layers.iter().filter(|x| x.is_trainable());
 
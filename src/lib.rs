@@ -1,6 +1,9 @@
 #![crate_name = "micrograd_rs"]
 
 mod ops;
+pub use ops::{CustomFn, CustomOp};
+
+pub mod autograd;
 
 pub mod optimizers;
 pub use optimizers as optim;
@@ -17,8 +20,20 @@ pub use layers::*;
 pub mod prelude;
 pub mod utils;
 
+mod einsum;
+pub use einsum::{einsum, EinsumOperand};
+
+mod float;
+pub use float::{Float, Scalar};
+
+mod shared;
+pub use shared::MaybeSync;
+
 mod tensor;
-pub use tensor::Tensor;
+pub use tensor::{outer, Tensor, TensorGrad, TensorReduce};
+
+mod tensor_value;
+pub use tensor_value::{BroadcastInto, TensorValue};
 
 mod value;
 pub use value::Value;
@@ -1,5 +1,6 @@
 use crate::optimizers::Optimizer;
 use crate::value::Value;
+use crate::Float;
 
 mod constant_lr;
 mod cosine_annealing_lr;
@@ -30,17 +31,17 @@ pub trait Schedule {
     }
 }
 
-pub struct LRScheduler<S: Schedule> {
-    lr: Value,
+pub struct LRScheduler<S: Schedule, F: Float = f64> {
+    lr: Value<F>,
     schedule: S,
     base_lr: f64,
     last_epoch: usize,
 }
 
-impl<S: Schedule> LRScheduler<S> {
-    pub fn new<O: Optimizer>(optimizer: &O, schedule: S) -> Self {
+impl<S: Schedule, F: Float> LRScheduler<S, F> {
+    pub fn new<O: Optimizer<F>>(optimizer: &O, schedule: S) -> Self {
         let lr = optimizer.lr();
-        let base_lr = lr.value();
+        let base_lr = lr.value().as_f64();
         let last_epoch = 0;
 
         let mut scheduler = LRScheduler {
@@ -55,9 +56,9 @@ impl<S: Schedule> LRScheduler<S> {
     }
 
     pub fn step(&mut self) {
-        let new_lr = self.schedule.get_lr(self.lr.value(), self.last_epoch);
+        let new_lr = self.schedule.get_lr(self.lr(), self.last_epoch);
 
-        *self.lr.value_mut() = new_lr.into();
+        *self.lr.value_mut() = F::cast(new_lr);
         self.last_epoch += 1;
     }
 
@@ -65,14 +66,14 @@ impl<S: Schedule> LRScheduler<S> {
         let new_lr = if S::HAS_CLOSED_FORM {
             self.schedule.get_closed_form_lr(self.base_lr, epoch)
         } else {
-            self.schedule.get_lr(self.lr.value(), epoch)
+            self.schedule.get_lr(self.lr(), epoch)
         };
 
-        *self.lr.value_mut() = new_lr.into();
+        *self.lr.value_mut() = F::cast(new_lr);
         self.last_epoch = epoch;
     }
 
     pub fn lr(&self) -> f64 {
-        self.lr.value()
+        self.lr.value().as_f64()
     }
 }
@@ -1,15 +1,63 @@
 use super::{Op, Value};
+use crate::float::Float;
 
-pub enum BinaryOps {
-    Add(Value, Value),
-    Sub(Value, Value),
-    Mul(Value, Value),
-    Div(Value, Value),
-    Pow(Value, Value),
+pub enum BinaryOps<F: Float> {
+    Add(Value<F>, Value<F>),
+    Sub(Value<F>, Value<F>),
+    Mul(Value<F>, Value<F>),
+    Div(Value<F>, Value<F>),
+    Pow(Value<F>, Value<F>),
 }
 
-impl Op for BinaryOps {
-    fn into_inner(self) -> Vec<Value> {
+/// A binary op without its operands, holding the scalar math shared by graph
+/// nodes and captured programs.
+#[derive(Clone, Copy)]
+pub(crate) enum BinaryKind {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+impl BinaryKind {
+    pub(crate) fn forward<F: Float>(self, x: F, y: F) -> F {
+        match self {
+            Self::Add => x + y,
+            Self::Sub => x - y,
+            Self::Mul => x * y,
+            Self::Div => x / y,
+            Self::Pow => x.powf(y),
+        }
+    }
+
+    /// Partials with respect to `x` and `y`, given the output `out` of the
+    /// forward pass.
+    pub(crate) fn partials<F: Float>(self, x: F, y: F, out: F) -> [F; 2] {
+        match self {
+            Self::Add => [F::one(), F::one()],
+            Self::Sub => [F::one(), -F::one()],
+            Self::Mul => [y, x],
+            Self::Div => [F::one() / y, -x / y.powi(2)],
+            Self::Pow => [y * x.powf(y - F::one()), x.ln() * out],
+        }
+    }
+}
+
+impl<F: Float> BinaryOps<F> {
+    pub(crate) fn kind(&self) -> BinaryKind {
+        match self {
+            Self::Add(_, _) => BinaryKind::Add,
+            Self::Sub(_, _) => BinaryKind::Sub,
+            Self::Mul(_, _) => BinaryKind::Mul,
+            Self::Div(_, _) => BinaryKind::Div,
+            Self::Pow(_, _) => BinaryKind::Pow,
+        }
+    }
+}
+
+impl<F: Float> Op<F> for BinaryOps<F> {
+    fn into_inner(self) -> Vec<Value<F>> {
         match self {
             Self::Add(lhs, rhs) => vec![lhs, rhs],
             Self::Sub(lhs, rhs) => vec![lhs, rhs],
@@ -19,7 +67,7 @@ impl Op for BinaryOps {
         }
     }
 
-    fn variables(&self) -> Vec<&Value> {
+    fn variables(&self) -> Vec<&Value<F>> {
         match self {
             Self::Add(lhs, rhs) => vec![lhs, rhs],
             Self::Sub(lhs, rhs) => vec![lhs, rhs],
@@ -29,57 +77,65 @@ impl Op for BinaryOps {
         }
     }
 
-    fn propagate(&self, source: &Value) {
-        let grad = &source
-            .grad()
-            .unwrap_or_else(|| panic!("Cannot backpropagate when gradient is None"));
+    fn partials(&self, source: &Value<F>) -> Vec<F> {
+        let [x, y] = [self.variables()[0], self.variables()[1]].map(Value::value);
 
+        self.kind().partials(x, y, source.value()).to_vec()
+    }
+
+    fn propagate(&self, _source: &Value<F>, grad: &Value<F>) -> Vec<Option<Value<F>>> {
         match self {
             Self::Add(lhs, rhs) => {
-                if lhs.should_compute_grad() {
-                    *lhs.grad_mut() += grad;
-                }
+                let lhs_grad = lhs.should_compute_grad().then(|| grad.clone());
+                let rhs_grad = rhs.should_compute_grad().then(|| grad.clone());
 
-                if rhs.should_compute_grad() {
-                    *rhs.grad_mut() += grad;
-                }
+                vec![lhs_grad, rhs_grad]
             }
             Self::Sub(lhs, rhs) => {
-                if lhs.should_compute_grad() {
-                    *lhs.grad_mut() += grad;
-                }
+                let lhs_grad = lhs.should_compute_grad().then(|| grad.clone());
+                let rhs_grad = rhs.should_compute_grad().then(|| -grad);
 
-                if rhs.should_compute_grad() {
-                    *rhs.grad_mut() += -grad;
-                }
+                vec![lhs_grad, rhs_grad]
             }
             Self::Mul(lhs, rhs) => {
-                if lhs.should_compute_grad() {
-                    *lhs.grad_mut() += grad * rhs;
-                }
-                if rhs.should_compute_grad() {
-                    *rhs.grad_mut() += grad * lhs;
-                }
+                let lhs_grad = lhs.should_compute_grad().then(|| grad * rhs);
+                let rhs_grad = rhs.should_compute_grad().then(|| grad * lhs);
+
+                vec![lhs_grad, rhs_grad]
             }
             Self::Div(numer, denom) => {
-                if numer.should_compute_grad() {
-                    *numer.grad_mut() += grad / denom;
-                }
-                if denom.should_compute_grad() {
-                    let derivative = -(numer / &denom.powf(2.0));
-                    *denom.grad_mut() += grad * &derivative;
-                }
+                let numer_grad = numer.should_compute_grad().then(|| grad / denom);
+                let denom_grad = denom.should_compute_grad().then(|| {
+                    let derivative = -(numer / &denom.powf(F::cast(2.0)));
+                    grad * &derivative
+                });
+
+                vec![numer_grad, denom_grad]
             }
             Self::Pow(variable, exponent) => {
-                if variable.should_compute_grad() {
-                    let wrt_variable = &variable.pow(exponent - &1.0) * exponent;
-                    *variable.grad_mut() += grad * &wrt_variable;
-                }
-                if exponent.should_compute_grad() {
+                let variable_grad = variable.should_compute_grad().then(|| {
+                    let wrt_variable = &variable.pow(exponent - &F::one()) * exponent;
+                    grad * &wrt_variable
+                });
+                let exponent_grad = exponent.should_compute_grad().then(|| {
                     let wrt_exponent = variable.log() * variable.pow(exponent.clone());
-                    *exponent.grad_mut() += grad * &wrt_exponent;
-                }
+                    grad * &wrt_exponent
+                });
+
+                vec![variable_grad, exponent_grad]
             }
+        }
+    }
+
+    fn name(&self) -> String {
+        let name = match self {
+            Self::Add(_, _) => "Add",
+            Self::Sub(_, _) => "Sub",
+            Self::Mul(_, _) => "Mul",
+            Self::Div(_, _) => "Div",
+            Self::Pow(_, _) => "Pow",
         };
+
+        String::from(name)
     }
 }
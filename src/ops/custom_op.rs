@@ -0,0 +1,83 @@
+use super::{Op, Value};
+use crate::autograd::no_grad;
+use crate::float::Float;
+use crate::shared::MaybeSync;
+
+/// A differentiable operation with a hand-written derivative. `backward`
+/// receives the op's output and the gradient flowing into it, and returns one
+/// gradient per operand, in the same order as `operands`.
+pub trait CustomOp<F: Float = f64>: MaybeSync {
+    fn operands(&self) -> Vec<&Value<F>>;
+    fn into_operands(self: Box<Self>) -> Vec<Value<F>>;
+    fn backward(&self, output: &Value<F>, grad: &Value<F>) -> Vec<Value<F>>;
+
+    fn name(&self) -> String {
+        String::from("Custom")
+    }
+}
+
+pub struct CustomFn<B, F: Float = f64> {
+    pub operands: Vec<Value<F>>,
+    pub backward: B,
+}
+
+impl<B, F> CustomOp<F> for CustomFn<B, F>
+where
+    B: Fn(&[Value<F>], &Value<F>, &Value<F>) -> Vec<Value<F>> + MaybeSync,
+    F: Float,
+{
+    fn operands(&self) -> Vec<&Value<F>> {
+        self.operands.iter().collect()
+    }
+
+    fn into_operands(self: Box<Self>) -> Vec<Value<F>> {
+        self.operands
+    }
+
+    fn backward(&self, output: &Value<F>, grad: &Value<F>) -> Vec<Value<F>> {
+        (self.backward)(&self.operands, output, grad)
+    }
+}
+
+impl<F: Float> Op<F> for Box<dyn CustomOp<F>> {
+    fn into_inner(self) -> Vec<Value<F>> {
+        self.into_operands()
+    }
+
+    fn variables(&self) -> Vec<&Value<F>> {
+        self.operands()
+    }
+
+    fn partials(&self, source: &Value<F>) -> Vec<F> {
+        let _guard = no_grad();
+        let partials = self.propagate(source, &Value::new(F::one()));
+
+        partials
+            .into_iter()
+            .map(|partial| partial.map_or(F::zero(), |partial| partial.value()))
+            .collect()
+    }
+
+    fn propagate(&self, source: &Value<F>, grad: &Value<F>) -> Vec<Option<Value<F>>> {
+        let operands = self.operands();
+        let operand_grads = self.backward(source, grad);
+        assert_eq!(
+            operands.len(),
+            operand_grads.len(),
+            "Custom op \"{}\" returned {} gradients for {} operands",
+            Op::name(self),
+            operand_grads.len(),
+            operands.len()
+        );
+
+        operands
+            .into_iter()
+            .zip(operand_grads)
+            .map(|(operand, grad)| operand.should_compute_grad().then_some(grad))
+            .collect()
+    }
+
+    fn name(&self) -> String {
+        CustomOp::name(self.as_ref())
+    }
+}
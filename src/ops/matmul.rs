@@ -0,0 +1,143 @@
+use ndarray::{Array2, ArrayView2};
+
+use super::{Op, Ops, Value};
+use crate::autograd::engine::Grad;
+use crate::float::Float;
+use crate::tensor::{self, Tensor};
+
+/// A matrix product is recorded as one hidden `Product` node holding both
+/// operands, plus one `Element` node per output pointing back at it. The
+/// engine collects the gradients of every element before reaching the
+/// product, which then backpropagates them all at once.
+///
+/// The product is left out of DOT exports, profiler node counts and anomaly
+/// reports, where each element points at its row and column instead.
+pub enum MatMulOps<F: Float> {
+    Product {
+        lhs: Vec<Value<F>>,
+        rhs: Vec<Value<F>>,
+        dims: (usize, usize, usize),
+    },
+    Element(Value<F>, usize),
+}
+
+impl<F: Float> Op<F> for MatMulOps<F> {
+    fn into_inner(self) -> Vec<Value<F>> {
+        match self {
+            Self::Product { lhs, rhs, .. } => lhs.into_iter().chain(rhs).collect(),
+            Self::Element(product, _) => vec![product],
+        }
+    }
+
+    fn variables(&self) -> Vec<&Value<F>> {
+        match self {
+            Self::Product { lhs, rhs, .. } => lhs.iter().chain(rhs).collect(),
+            Self::Element(product, _) => vec![product],
+        }
+    }
+
+    // Only elements are differentiated one at a time. Their gradient is handed
+    // to the product unchanged, and the product itself goes through `backward`.
+    fn partials(&self, _source: &Value<F>) -> Vec<F> {
+        match self {
+            Self::Product { lhs, rhs, .. } => vec![F::zero(); lhs.len() + rhs.len()],
+            Self::Element(_, _) => vec![F::one()],
+        }
+    }
+
+    fn propagate(&self, _source: &Value<F>, grad: &Value<F>) -> Vec<Option<Value<F>>> {
+        match self {
+            Self::Product { lhs, rhs, .. } => vec![None; lhs.len() + rhs.len()],
+            Self::Element(_, _) => vec![Some(grad.clone())],
+        }
+    }
+
+    fn name(&self) -> String {
+        let name = match self {
+            Self::Product { .. } => "MatMul",
+            Self::Element(_, _) => "MatMulElement",
+        };
+
+        String::from(name)
+    }
+}
+
+impl<F: Float> MatMulOps<F> {
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Self::Product {
+                dims: (m, _, n), ..
+            } => m * n,
+            Self::Element(_, _) => 1,
+        }
+    }
+
+    pub(crate) fn visible_operands(&self) -> Vec<Value<F>> {
+        let Self::Element(product, idx) = self else {
+            return self.variables().into_iter().cloned().collect();
+        };
+        let Ops::MatMul(Self::Product {
+            lhs,
+            rhs,
+            dims: (_, k, n),
+        }) = product.operation()
+        else {
+            unreachable!("Matrix product elements point at their product");
+        };
+
+        let (i, j) = (idx / n, idx % n);
+        lhs[i * k..(i + 1) * k]
+            .iter()
+            .chain(rhs.iter().skip(j).step_by(*n))
+            .cloned()
+            .collect()
+    }
+
+    /// Gradients of `lhs` followed by `rhs`, computed as `grad · rhsᵀ` and
+    /// `lhsᵀ · grad` from the gradient of every output element.
+    pub(crate) fn backward(&self, output_grads: Vec<Option<Grad<F>>>) -> Vec<Grad<F>> {
+        let Self::Product { lhs, rhs, dims } = self else {
+            unreachable!("Only products backpropagate in bulk");
+        };
+        let (m, k, n) = *dims;
+
+        let create_graph = output_grads
+            .iter()
+            .any(|grad| matches!(grad, Some(Grad::Graph(_))));
+        if create_graph {
+            let grad = Tensor::from_shape_vec(
+                (m, n),
+                output_grads
+                    .into_iter()
+                    .map(|grad| grad.map_or_else(|| Value::constant(F::zero()), Grad::into_value))
+                    .collect(),
+            )
+            .unwrap();
+            let lhs = ArrayView2::from_shape((m, k), lhs).unwrap();
+            let rhs = ArrayView2::from_shape((k, n), rhs).unwrap();
+
+            let lhs_grad = tensor::matmul(grad.view(), rhs.t());
+            let rhs_grad = tensor::matmul(lhs.t(), grad.view());
+            return lhs_grad
+                .into_iter()
+                .chain(rhs_grad)
+                .map(Grad::Graph)
+                .collect();
+        }
+
+        let grad = Array2::from_shape_fn((m, n), |(i, j)| match output_grads[i * n + j] {
+            Some(Grad::Raw(grad)) => grad,
+            _ => F::zero(),
+        });
+        let lhs = Array2::from_shape_fn((m, k), |(i, j)| lhs[i * k + j].value());
+        let rhs = Array2::from_shape_fn((k, n), |(i, j)| rhs[i * n + j].value());
+
+        let lhs_grad = grad.dot(&rhs.t());
+        let rhs_grad = lhs.t().dot(&grad);
+        lhs_grad
+            .iter()
+            .chain(rhs_grad.iter())
+            .map(|grad| Grad::Raw(*grad))
+            .collect()
+    }
+}
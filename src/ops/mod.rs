@@ -1,58 +1,113 @@
-mod binary_ops;
-mod unary_ops;
+pub(crate) mod binary_ops;
+mod custom_op;
+mod matmul;
+pub(crate) mod unary_ops;
 
 pub use self::binary_ops::BinaryOps;
+pub use self::custom_op::{CustomFn, CustomOp};
+pub use self::matmul::MatMulOps;
 pub use self::unary_ops::UnaryOps;
 
+use super::float::Float;
 use super::value::Value;
 
-pub trait Op {
-    fn into_inner(self) -> Vec<Value>;
-    fn variables(&self) -> Vec<&Value>;
-    fn propagate(&self, source: &Value);
+pub trait Op<F: Float> {
+    fn into_inner(self) -> Vec<Value<F>>;
+    fn variables(&self) -> Vec<&Value<F>>;
+    fn partials(&self, source: &Value<F>) -> Vec<F>;
+    fn propagate(&self, source: &Value<F>, grad: &Value<F>) -> Vec<Option<Value<F>>>;
+    fn name(&self) -> String;
 }
 
 #[derive(Default)]
-pub enum Ops {
-    Binary(BinaryOps),
-    Unary(UnaryOps),
+pub enum Ops<F: Float> {
+    Binary(BinaryOps<F>),
+    Unary(UnaryOps<F>),
+    MatMul(MatMulOps<F>),
+    Custom(Box<dyn CustomOp<F>>),
     #[default]
     NoOp,
 }
 
-impl Op for Ops {
-    fn into_inner(self) -> Vec<Value> {
+impl<F: Float> Op<F> for Ops<F> {
+    fn into_inner(self) -> Vec<Value<F>> {
         match self {
             Self::Binary(bin_ops) => bin_ops.into_inner(),
             Self::Unary(unary_ops) => unary_ops.into_inner(),
+            Self::MatMul(matmul) => matmul.into_inner(),
+            Self::Custom(custom_op) => custom_op.into_inner(),
             Self::NoOp => vec![],
         }
     }
 
-    fn variables(&self) -> Vec<&Value> {
+    fn variables(&self) -> Vec<&Value<F>> {
         match self {
             Self::Binary(bin_ops) => bin_ops.variables(),
             Self::Unary(unary_ops) => unary_ops.variables(),
+            Self::MatMul(matmul) => matmul.variables(),
+            Self::Custom(custom_op) => custom_op.variables(),
             Self::NoOp => vec![],
         }
     }
 
-    fn propagate(&self, source: &Value) {
+    fn partials(&self, source: &Value<F>) -> Vec<F> {
         match self {
-            Self::Binary(bin_ops) => bin_ops.propagate(source),
-            Self::Unary(unary_ops) => unary_ops.propagate(source),
-            Self::NoOp => (),
+            Self::Binary(bin_ops) => bin_ops.partials(source),
+            Self::Unary(unary_ops) => unary_ops.partials(source),
+            Self::MatMul(matmul) => matmul.partials(source),
+            Self::Custom(custom_op) => custom_op.partials(source),
+            Self::NoOp => vec![],
         }
     }
+
+    fn propagate(&self, source: &Value<F>, grad: &Value<F>) -> Vec<Option<Value<F>>> {
+        match self {
+            Self::Binary(bin_ops) => bin_ops.propagate(source, grad),
+            Self::Unary(unary_ops) => unary_ops.propagate(source, grad),
+            Self::MatMul(matmul) => matmul.propagate(source, grad),
+            Self::Custom(custom_op) => custom_op.propagate(source, grad),
+            Self::NoOp => vec![],
+        }
+    }
+
+    fn name(&self) -> String {
+        match self {
+            Self::Binary(bin_ops) => bin_ops.name(),
+            Self::Unary(unary_ops) => unary_ops.name(),
+            Self::MatMul(matmul) => matmul.name(),
+            Self::Custom(custom_op) => Op::name(custom_op),
+            Self::NoOp => String::from("Leaf"),
+        }
+    }
+}
+
+impl<F: Float> Ops<F> {
+    /// Operands as shown to users, where elements of a fused matrix product
+    /// point at their row and column instead of the hidden product node.
+    pub(crate) fn visible_operands(&self) -> Vec<Value<F>> {
+        match self {
+            Self::MatMul(matmul) => matmul.visible_operands(),
+            operation => operation.variables().into_iter().cloned().collect(),
+        }
+    }
+
+    pub(crate) fn is_hidden(&self) -> bool {
+        matches!(self, Self::MatMul(MatMulOps::Product { .. }))
+    }
 }
 
 macro_rules! impl_into_ops {
     [$(($op: ty, $varient: ident)),*] => {
-        $(impl From<$op> for Ops {
+        $(impl<F: Float> From<$op> for Ops<F> {
             fn from(bin_ops: $op) -> Self {
                 Ops::$varient(bin_ops)
             }
         })*
     };
 }
-impl_into_ops![(BinaryOps, Binary), (UnaryOps, Unary)];
+impl_into_ops![
+    (BinaryOps<F>, Binary),
+    (UnaryOps<F>, Unary),
+    (MatMulOps<F>, MatMul),
+    (Box<dyn CustomOp<F>>, Custom)
+];
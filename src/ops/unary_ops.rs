@@ -1,51 +1,253 @@
+use std::f64::consts::{E, PI};
+
 use super::{Op, Value};
+use crate::float::Float;
+
+pub enum UnaryOps<F: Float> {
+    Exp(Value<F>),
+    Log(Value<F>),
+    ReLU(Value<F>),
+    Sin(Value<F>),
+    Cos(Value<F>),
+    Tanh(Value<F>),
+    Sigmoid(Value<F>),
+    Abs(Value<F>),
+    Softplus(Value<F>),
+    Erf(Value<F>),
+    Clamp(Value<F>, F, F),
+    Log1p(Value<F>),
+    Expm1(Value<F>),
+}
+
+/// A unary op without its operand, holding the scalar math shared by graph
+/// nodes and captured programs.
+#[derive(Clone, Copy)]
+pub(crate) enum UnaryKind<F> {
+    Exp,
+    Log,
+    ReLU,
+    Sin,
+    Cos,
+    Tanh,
+    Sigmoid,
+    Abs,
+    Softplus,
+    Erf,
+    Clamp(F, F),
+    Log1p,
+    Expm1,
+}
+
+impl<F: Float> UnaryKind<F> {
+    pub(crate) fn forward(self, x: F) -> F {
+        match self {
+            Self::Exp => F::cast(E).powf(x),
+            Self::Log => x.ln(),
+            Self::ReLU => x.max(F::zero()),
+            Self::Sin => x.sin(),
+            Self::Cos => x.cos(),
+            Self::Tanh => x.tanh(),
+            Self::Sigmoid => sigmoid(x),
+            Self::Abs => x.abs(),
+            Self::Softplus => softplus(x),
+            Self::Erf => F::cast(erf(x.as_f64())),
+            Self::Clamp(min, max) => num_traits::clamp(x, min, max),
+            Self::Log1p => x.ln_1p(),
+            Self::Expm1 => x.exp_m1(),
+        }
+    }
+
+    /// Derivative at `x`, given the output `out` of the forward pass.
+    pub(crate) fn derivative(self, x: F, out: F) -> F {
+        match self {
+            Self::Exp => out,
+            Self::Log => F::one() / x,
+            Self::ReLU => out.ceil().min(F::one()),
+            Self::Sin => x.cos(),
+            Self::Cos => -x.sin(),
+            Self::Tanh => F::one() - out.powi(2),
+            Self::Sigmoid => out * (F::one() - out),
+            Self::Abs => sign(x),
+            Self::Softplus => sigmoid(x),
+            Self::Erf => erf_derivative(x),
+            Self::Clamp(min, max) => in_range(x, min, max),
+            Self::Log1p => F::one() / (F::one() + x),
+            Self::Expm1 => out + F::one(),
+        }
+    }
+}
 
-pub enum UnaryOps {
-    Exp(Value),
-    Log(Value),
-    ReLU(Value),
+impl<F: Float> UnaryOps<F> {
+    pub(crate) fn kind(&self) -> UnaryKind<F> {
+        match self {
+            Self::Exp(_) => UnaryKind::Exp,
+            Self::Log(_) => UnaryKind::Log,
+            Self::ReLU(_) => UnaryKind::ReLU,
+            Self::Sin(_) => UnaryKind::Sin,
+            Self::Cos(_) => UnaryKind::Cos,
+            Self::Tanh(_) => UnaryKind::Tanh,
+            Self::Sigmoid(_) => UnaryKind::Sigmoid,
+            Self::Abs(_) => UnaryKind::Abs,
+            Self::Softplus(_) => UnaryKind::Softplus,
+            Self::Erf(_) => UnaryKind::Erf,
+            Self::Clamp(_, min, max) => UnaryKind::Clamp(*min, *max),
+            Self::Log1p(_) => UnaryKind::Log1p,
+            Self::Expm1(_) => UnaryKind::Expm1,
+        }
+    }
 }
 
-impl Op for UnaryOps {
-    fn into_inner(self) -> Vec<Value> {
+impl<F: Float> Op<F> for UnaryOps<F> {
+    fn into_inner(self) -> Vec<Value<F>> {
         match self {
             Self::Exp(value) => vec![value],
             Self::Log(value) => vec![value],
             Self::ReLU(value) => vec![value],
+            Self::Sin(value) => vec![value],
+            Self::Cos(value) => vec![value],
+            Self::Tanh(value) => vec![value],
+            Self::Sigmoid(value) => vec![value],
+            Self::Abs(value) => vec![value],
+            Self::Softplus(value) => vec![value],
+            Self::Erf(value) => vec![value],
+            Self::Clamp(value, _, _) => vec![value],
+            Self::Log1p(value) => vec![value],
+            Self::Expm1(value) => vec![value],
         }
     }
 
-    fn variables(&self) -> Vec<&Value> {
+    fn variables(&self) -> Vec<&Value<F>> {
         match self {
             Self::Exp(value) => vec![value],
             Self::Log(value) => vec![value],
             Self::ReLU(value) => vec![value],
+            Self::Sin(value) => vec![value],
+            Self::Cos(value) => vec![value],
+            Self::Tanh(value) => vec![value],
+            Self::Sigmoid(value) => vec![value],
+            Self::Abs(value) => vec![value],
+            Self::Softplus(value) => vec![value],
+            Self::Erf(value) => vec![value],
+            Self::Clamp(value, _, _) => vec![value],
+            Self::Log1p(value) => vec![value],
+            Self::Expm1(value) => vec![value],
         }
     }
 
-    fn propagate(&self, source: &Value) {
-        let grad = &source
-            .grad()
-            .unwrap_or_else(|| panic!("Cannot backpropagate when gradient is None"));
+    fn partials(&self, source: &Value<F>) -> Vec<F> {
+        let variable = self.variables()[0];
 
-        match self {
-            Self::Exp(exponent) => {
-                if exponent.should_compute_grad() {
-                    *exponent.grad_mut() += grad * source
-                }
+        vec![self.kind().derivative(variable.value(), source.value())]
+    }
+
+    fn propagate(&self, source: &Value<F>, grad: &Value<F>) -> Vec<Option<Value<F>>> {
+        let variable = self.variables()[0];
+        if !variable.should_compute_grad() {
+            return vec![None];
+        }
+
+        let variable_grad = match self {
+            Self::Exp(_) => grad * source,
+            Self::Log(_) => grad / variable,
+            Self::ReLU(_) => {
+                let one_if_greater_than_zero = source.value().ceil().min(F::one());
+                grad * &one_if_greater_than_zero
             }
-            Self::Log(variable) => {
-                if variable.should_compute_grad() {
-                    *variable.grad_mut() += grad / source
-                }
+            Self::Sin(_) => grad * &variable.cos(),
+            Self::Cos(_) => -(grad * &variable.sin()),
+            Self::Tanh(_) => grad - &(grad * &source.powf(F::cast(2.0))),
+            Self::Sigmoid(_) => grad * &(source - &source.powf(F::cast(2.0))),
+            Self::Abs(_) => grad * &sign(variable.value()),
+            Self::Softplus(_) => grad * &variable.sigmoid(),
+            Self::Erf(_) => {
+                let derivative = (-variable.powf(F::cast(2.0))).exp() * F::cast(2.0 / PI.sqrt());
+                grad * &derivative
             }
-            Self::ReLU(unactivated) => {
-                if unactivated.should_compute_grad() {
-                    let one_if_greater_than_zero = source.value().ceil().min(1.0);
+            Self::Clamp(_, min, max) => grad * &in_range(variable.value(), *min, *max),
+            Self::Log1p(_) => grad / &(variable + &F::one()),
+            Self::Expm1(_) => grad * &(source + &F::one()),
+        };
 
-                    *unactivated.grad_mut() += grad * &one_if_greater_than_zero;
-                }
-            }
+        vec![Some(variable_grad)]
+    }
+
+    fn name(&self) -> String {
+        let name = match self {
+            Self::Exp(_) => "Exp",
+            Self::Log(_) => "Log",
+            Self::ReLU(_) => "ReLU",
+            Self::Sin(_) => "Sin",
+            Self::Cos(_) => "Cos",
+            Self::Tanh(_) => "Tanh",
+            Self::Sigmoid(_) => "Sigmoid",
+            Self::Abs(_) => "Abs",
+            Self::Softplus(_) => "Softplus",
+            Self::Erf(_) => "Erf",
+            Self::Clamp(_, _, _) => "Clamp",
+            Self::Log1p(_) => "Log1p",
+            Self::Expm1(_) => "Expm1",
         };
+
+        String::from(name)
+    }
+}
+
+pub(crate) fn sign<F: Float>(x: F) -> F {
+    if x.is_zero() {
+        F::zero()
+    } else {
+        x.signum()
+    }
+}
+
+pub(crate) fn in_range<F: Float>(x: F, min: F, max: F) -> F {
+    if (min..=max).contains(&x) {
+        F::one()
+    } else {
+        F::zero()
     }
 }
+
+pub(crate) fn sigmoid<F: Float>(x: F) -> F {
+    if x >= F::zero() {
+        F::one() / (F::one() + (-x).exp())
+    } else {
+        let exp = x.exp();
+        exp / (F::one() + exp)
+    }
+}
+
+pub(crate) fn softplus<F: Float>(x: F) -> F {
+    x.max(F::zero()) + (-x.abs()).exp().ln_1p()
+}
+
+pub(crate) fn erf_derivative<F: Float>(x: F) -> F {
+    F::cast(2.0 / PI.sqrt()) * (-x.powi(2)).exp()
+}
+
+pub(crate) fn erf(x: f64) -> f64 {
+    if x.abs() > 2.0 {
+        return x.signum() * (1.0 - erfc_continued_fraction(x.abs()));
+    }
+
+    let (mut term, mut sum) = (x, x);
+    for n in 1..100 {
+        term *= -x.powi(2) / n as f64;
+        let next = term / (2 * n + 1) as f64;
+        sum += next;
+
+        if next.abs() < f64::EPSILON * sum.abs() {
+            break;
+        }
+    }
+
+    (2.0 / PI.sqrt()) * sum
+}
+
+fn erfc_continued_fraction(x: f64) -> f64 {
+    let fraction = (1..=60)
+        .rev()
+        .fold(x, |fraction, k| x + (k as f64 / 2.0) / fraction);
+
+    (-x.powi(2)).exp() / (PI.sqrt() * fraction)
+}
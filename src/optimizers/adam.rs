@@ -2,32 +2,32 @@ use super::Optimizer;
 use crate::prelude::*;
 
 #[derive(Default)]
-pub struct AdamCache {
+pub struct AdamCache<F = f64> {
     pub time_step: usize,
-    pub exp_avgs: Option<Array1<f64>>,
-    pub exp_avg_sqs: Option<Array1<f64>>,
-    pub max_exp_avg_sqs: Option<Array1<f64>>,
+    pub exp_avgs: Option<Array1<F>>,
+    pub exp_avg_sqs: Option<Array1<F>>,
+    pub max_exp_avg_sqs: Option<Array1<F>>,
 }
 
-pub struct Adam {
-    pub params: Vec<Value>,
-    pub lr: Value,
-    pub betas: (f64, f64),
-    pub eps: f64,
-    pub weight_decay: f64,
+pub struct Adam<F: Float = f64> {
+    pub params: Vec<Value<F>>,
+    pub lr: Value<F>,
+    pub betas: (F, F),
+    pub eps: F,
+    pub weight_decay: F,
     pub amsgrad: bool,
     pub maximize: bool,
-    pub cache: AdamCache,
+    pub cache: AdamCache<F>,
 }
 
-impl Default for Adam {
+impl<F: Float> Default for Adam<F> {
     fn default() -> Self {
         Adam {
             params: vec![],
-            lr: val!(0.001),
-            betas: (0.9, 0.999),
-            eps: 1e-8,
-            weight_decay: 0.0,
+            lr: Value::new(F::cast(0.001)),
+            betas: (F::cast(0.9), F::cast(0.999)),
+            eps: F::cast(1e-8),
+            weight_decay: F::zero(),
             amsgrad: false,
             maximize: false,
             cache: Default::default(),
@@ -35,7 +35,7 @@ impl Default for Adam {
     }
 }
 
-impl Optimizer for Adam {
+impl<F: Float> Optimizer<F> for Adam<F> {
     fn step(&mut self) {
         let params_n = self.params.len();
         let (beta1, beta2) = self.betas;
@@ -46,9 +46,10 @@ impl Optimizer for Adam {
             ref mut max_exp_avg_sqs,
         } = self.cache;
 
-        let exp_avgs = exp_avgs.get_or_insert(Array1::from_vec(vec![0.; params_n]));
-        let exp_avg_sqs = exp_avg_sqs.get_or_insert(Array1::from_vec(vec![0.; params_n]));
-        let max_exp_avg_sqs = max_exp_avg_sqs.get_or_insert(Array1::from_vec(vec![0.; params_n]));
+        let exp_avgs = exp_avgs.get_or_insert(Array1::from_vec(vec![F::zero(); params_n]));
+        let exp_avg_sqs = exp_avg_sqs.get_or_insert(Array1::from_vec(vec![F::zero(); params_n]));
+        let max_exp_avg_sqs =
+            max_exp_avg_sqs.get_or_insert(Array1::from_vec(vec![F::zero(); params_n]));
 
         for (i, param) in self.params.iter().enumerate() {
             let mut grad = param
@@ -61,12 +62,13 @@ impl Optimizer for Adam {
             }
             grad += param.value() * self.weight_decay;
 
-            exp_avgs[i] = (beta1 * exp_avgs[i]) + ((1. - beta1) * grad);
-            exp_avg_sqs[i] = (beta2 * exp_avg_sqs[i]) + ((1. - beta2) * grad.powf(2.0));
+            exp_avgs[i] = (beta1 * exp_avgs[i]) + ((F::one() - beta1) * grad);
+            exp_avg_sqs[i] =
+                (beta2 * exp_avg_sqs[i]) + ((F::one() - beta2) * grad.powf(F::cast(2.0)));
 
             let t = 1 + (*time_step as i32);
-            let bias_correction1 = 1.0 - beta1.powi(t);
-            let bias_correction2 = 1.0 - beta2.powi(t);
+            let bias_correction1 = F::one() - beta1.powi(t);
+            let bias_correction2 = F::one() - beta2.powi(t);
 
             let step_size = self.lr.value() / bias_correction1;
 
@@ -91,7 +93,7 @@ impl Optimizer for Adam {
         }
     }
 
-    fn lr(&self) -> Value {
+    fn lr(&self) -> Value<F> {
         self.lr.clone()
     }
 }
@@ -6,9 +6,10 @@ pub use self::adam::Adam;
 pub use self::rmsprop::RMSProp;
 pub use self::sgd::SGD;
 pub use crate::value::Value;
+use crate::Float;
 
-pub trait Optimizer {
+pub trait Optimizer<F: Float = f64> {
     fn step(&mut self);
     fn zero_grad(&mut self);
-    fn lr(&self) -> Value;
+    fn lr(&self) -> Value<F>;
 }
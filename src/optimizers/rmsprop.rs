@@ -2,33 +2,33 @@ use super::Optimizer;
 use crate::prelude::*;
 
 #[derive(Default)]
-pub struct RMSPropCache {
-    pub prev_gradients: Option<Array1<f64>>,
-    pub moving_avg: Option<Array1<f64>>,
-    pub avg_gradients: Option<Array1<f64>>,
+pub struct RMSPropCache<F = f64> {
+    pub prev_gradients: Option<Array1<F>>,
+    pub moving_avg: Option<Array1<F>>,
+    pub avg_gradients: Option<Array1<F>>,
 }
 
-pub struct RMSProp {
-    pub params: Vec<Value>,
-    pub lr: Value,
-    pub alpha: f64,
-    pub eps: f64,
-    pub momentum: f64,
-    pub weight_decay: f64,
+pub struct RMSProp<F: Float = f64> {
+    pub params: Vec<Value<F>>,
+    pub lr: Value<F>,
+    pub alpha: F,
+    pub eps: F,
+    pub momentum: F,
+    pub weight_decay: F,
     pub centered: bool,
     pub maximize: bool,
-    pub cache: RMSPropCache,
+    pub cache: RMSPropCache<F>,
 }
 
-impl Default for RMSProp {
+impl<F: Float> Default for RMSProp<F> {
     fn default() -> Self {
         RMSProp {
             params: vec![],
-            lr: val!(0.01),
-            alpha: 0.99,
-            eps: 1e-8,
-            momentum: 0.0,
-            weight_decay: 0.0,
+            lr: Value::new(F::cast(0.01)),
+            alpha: F::cast(0.99),
+            eps: F::cast(1e-8),
+            momentum: F::zero(),
+            weight_decay: F::zero(),
             centered: false,
             maximize: false,
             cache: Default::default(),
@@ -36,7 +36,7 @@ impl Default for RMSProp {
     }
 }
 
-impl Optimizer for RMSProp {
+impl<F: Float> Optimizer<F> for RMSProp<F> {
     fn step(&mut self) {
         let params_n = self.params.len();
         let RMSPropCache {
@@ -45,9 +45,10 @@ impl Optimizer for RMSProp {
             ref mut avg_gradients,
         } = self.cache;
 
-        let prev_grads = prev_gradients.get_or_insert(Array1::from_vec(vec![0.; params_n]));
-        let moving_avg = moving_avg.get_or_insert(Array1::from_vec(vec![0.; params_n]));
-        let avg_gradients = avg_gradients.get_or_insert(Array1::from_vec(vec![0.; params_n]));
+        let prev_grads = prev_gradients.get_or_insert(Array1::from_vec(vec![F::zero(); params_n]));
+        let moving_avg = moving_avg.get_or_insert(Array1::from_vec(vec![F::zero(); params_n]));
+        let avg_gradients =
+            avg_gradients.get_or_insert(Array1::from_vec(vec![F::zero(); params_n]));
 
         for (i, param) in self.params.iter().enumerate() {
             let mut grad = param
@@ -56,12 +57,14 @@ impl Optimizer for RMSProp {
                 .value();
             grad += param.value() * self.weight_decay;
 
-            moving_avg[i] = (self.alpha * moving_avg[i]) + ((1. - self.alpha) * grad.powf(2.0));
+            moving_avg[i] =
+                (self.alpha * moving_avg[i]) + ((F::one() - self.alpha) * grad.powf(F::cast(2.0)));
             let mut curr_moving_avg = moving_avg[i];
 
             if self.centered {
-                avg_gradients[i] = (self.alpha * avg_gradients[i]) + ((1. - self.alpha) * grad);
-                curr_moving_avg -= avg_gradients[i].powf(2.0);
+                avg_gradients[i] =
+                    (self.alpha * avg_gradients[i]) + ((F::one() - self.alpha) * grad);
+                curr_moving_avg -= avg_gradients[i].powf(F::cast(2.0));
             }
 
             let momentum = self.momentum * prev_grads[i];
@@ -81,7 +84,7 @@ impl Optimizer for RMSProp {
         }
     }
 
-    fn lr(&self) -> Value {
+    fn lr(&self) -> Value<F> {
         self.lr.clone()
     }
 }
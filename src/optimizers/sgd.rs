@@ -2,36 +2,36 @@ use super::Optimizer;
 use crate::prelude::*;
 
 #[derive(Default)]
-pub struct SGDCache {
+pub struct SGDCache<F = f64> {
     pub time_step: usize,
-    pub prev_gradients: Option<Array1<f64>>,
+    pub prev_gradients: Option<Array1<F>>,
 }
 
-pub struct SGD {
-    pub params: Vec<Value>,
-    pub lr: Value,
-    pub momentum: f64,
-    pub dampening: f64,
-    pub weight_decay: f64,
+pub struct SGD<F: Float = f64> {
+    pub params: Vec<Value<F>>,
+    pub lr: Value<F>,
+    pub momentum: F,
+    pub dampening: F,
+    pub weight_decay: F,
     pub maximize: bool,
-    pub cache: SGDCache,
+    pub cache: SGDCache<F>,
 }
 
-impl Default for SGD {
+impl<F: Float> Default for SGD<F> {
     fn default() -> Self {
         SGD {
             params: vec![],
-            lr: val!(0.01),
-            momentum: 0.0,
-            dampening: 0.0,
-            weight_decay: 0.0,
+            lr: Value::new(F::cast(0.01)),
+            momentum: F::zero(),
+            dampening: F::zero(),
+            weight_decay: F::zero(),
             maximize: false,
             cache: Default::default(),
         }
     }
 }
 
-impl Optimizer for SGD {
+impl<F: Float> Optimizer<F> for SGD<F> {
     fn step(&mut self) {
         let params_n = self.params.len();
         let SGDCache {
@@ -39,7 +39,7 @@ impl Optimizer for SGD {
             ref mut prev_gradients,
         } = self.cache;
 
-        let prev_grads = prev_gradients.get_or_insert(Array1::from_vec(vec![0.; params_n]));
+        let prev_grads = prev_gradients.get_or_insert(Array1::from_vec(vec![F::zero(); params_n]));
 
         for (i, param) in self.params.iter().enumerate() {
             let mut grad = param
@@ -49,7 +49,8 @@ impl Optimizer for SGD {
             grad += param.value() * self.weight_decay;
 
             if *time_step > 0 {
-                prev_grads[i] = (self.momentum * prev_grads[i]) + ((1. - self.dampening) * grad);
+                prev_grads[i] =
+                    (self.momentum * prev_grads[i]) + ((F::one() - self.dampening) * grad);
             } else {
                 prev_grads[i] = grad;
             }
@@ -70,7 +71,7 @@ impl Optimizer for SGD {
         }
     }
 
-    fn lr(&self) -> Value {
+    fn lr(&self) -> Value<F> {
         self.lr.clone()
     }
 }
@@ -1,4 +1,7 @@
-pub use crate::tensor::{DotProd, Tensor};
+pub use crate::einsum::einsum;
+pub use crate::float::Float;
+pub use crate::tensor::{outer, DotProd, Tensor, TensorGrad, TensorReduce};
+pub use crate::tensor_value::TensorValue;
 pub use crate::value::Value;
 pub use crate::{scalar, sequential, tensor, val, values};
 pub use ndarray::prelude::*;
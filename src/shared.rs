@@ -0,0 +1,99 @@
+//! Storage backing `Value`: `Rc<RefCell<_>>` by default, `Arc<RwLock<_>>` with
+//! the `sync` feature so graphs, layers and optimizers can move across threads.
+
+#[cfg(not(feature = "sync"))]
+pub(crate) use self::local::*;
+#[cfg(feature = "sync")]
+pub(crate) use self::sync::*;
+
+/// Implemented by every type when the `sync` feature is off, and by every
+/// `Send + Sync` type when it is on.
+#[cfg(not(feature = "sync"))]
+pub trait MaybeSync {}
+#[cfg(not(feature = "sync"))]
+impl<T: ?Sized> MaybeSync for T {}
+
+#[cfg(feature = "sync")]
+pub trait MaybeSync: Send + Sync {}
+#[cfg(feature = "sync")]
+impl<T: Send + Sync + ?Sized> MaybeSync for T {}
+
+#[cfg(not(feature = "sync"))]
+mod local {
+    pub(crate) use std::cell::{RefCell as Lock, RefMut as MappedMut};
+    pub(crate) use std::rc::Rc as Shared;
+
+    pub(crate) fn map_mut<U, T: ?Sized>(
+        guard: MappedMut<'_, U>,
+        project: fn(&mut U) -> &mut T,
+    ) -> MappedMut<'_, T> {
+        MappedMut::map(guard, project)
+    }
+}
+
+#[cfg(feature = "sync")]
+mod sync {
+    use std::ops::{Deref, DerefMut};
+    use std::sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+    pub(crate) use std::sync::Arc as Shared;
+
+    #[derive(Default)]
+    pub(crate) struct Lock<T>(RwLock<T>);
+
+    impl<T> Lock<T> {
+        pub(crate) fn new(value: T) -> Self {
+            Lock(RwLock::new(value))
+        }
+
+        pub(crate) fn borrow(&self) -> RwLockReadGuard<'_, T> {
+            self.0.read().unwrap_or_else(PoisonError::into_inner)
+        }
+
+        pub(crate) fn borrow_mut(&self) -> RwLockWriteGuard<'_, T> {
+            self.0.write().unwrap_or_else(PoisonError::into_inner)
+        }
+
+        pub(crate) fn get_mut(&mut self) -> &mut T {
+            self.0.get_mut().unwrap_or_else(PoisonError::into_inner)
+        }
+    }
+
+    // Keeps a lock guard of any type alive behind a projected pointer.
+    trait Guard {}
+    impl<T> Guard for T {}
+
+    pub struct MappedMut<'a, T: ?Sized> {
+        _guard: Box<dyn Guard + 'a>,
+        value: *mut T,
+    }
+
+    impl<T: ?Sized> Deref for MappedMut<'_, T> {
+        type Target = T;
+
+        fn deref(&self) -> &T {
+            // SAFETY: `value` points into the data locked by `_guard`, which
+            // stays exclusively held for as long as this struct lives.
+            unsafe { &*self.value }
+        }
+    }
+
+    impl<T: ?Sized> DerefMut for MappedMut<'_, T> {
+        fn deref_mut(&mut self) -> &mut T {
+            // SAFETY: see `deref`.
+            unsafe { &mut *self.value }
+        }
+    }
+
+    pub(crate) fn map_mut<'a, U, T: ?Sized>(
+        guard: RwLockWriteGuard<'a, U>,
+        project: fn(&mut U) -> &mut T,
+    ) -> MappedMut<'a, T> {
+        let mut guard = guard;
+        let value: *mut T = project(&mut guard);
+        MappedMut {
+            _guard: Box::new(guard),
+            value,
+        }
+    }
+}
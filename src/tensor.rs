@@ -1,4 +1,10 @@
+use crate::autograd::is_grad_enabled;
+#[cfg(feature = "tape")]
+use crate::autograd::{is_recording, profiler, tape};
+use crate::float::Float;
+use crate::ops::MatMulOps;
 use crate::prelude::*;
+use crate::shared::{MaybeSync, Shared};
 use ndarray::{Data, OwnedRepr};
 use std::iter::zip;
 
@@ -14,6 +20,9 @@ macro_rules! tensor {
     [$($x:expr),*] => {
         array!($($x),*).mapv(|elem: f64| val!(elem))
     };
+    ([$($x:expr),*]; $float:ty $(, $mth:ident = $val:expr)*) => {
+        array!($($x),*).mapv(|elem: $float| val!(elem; $float $(, $mth = $val)*))
+    };
 }
 
 #[macro_export]
@@ -30,107 +39,443 @@ macro_rules! scalar {
     };
 }
 
-pub type Tensor<D> = ArrayBase<OwnedRepr<Value>, D>;
+pub type Tensor<D, F = f64> = ArrayBase<OwnedRepr<Value<F>>, D>;
 
-pub trait DotProd<Rhs> {
-    type Output;
+pub trait TensorGrad<D: Dimension, F: Float = f64> {
+    fn register_hook<H>(&self, hook: H)
+    where
+        H: Fn(&Value<F>) -> Option<Value<F>> + MaybeSync + 'static;
 
-    fn dot(&self, b: &Rhs) -> Self::Output;
+    fn retain_grad(&self);
+
+    fn detach(&self) -> Tensor<D, F>;
+
+    /// Forward-mode tangents of every element, zero where none is carried.
+    fn tangents(&self) -> Array<F, D>;
+
+    /// Wraps every element of an `f64` array in a new leaf, cast to `F`.
+    fn from_f64<T: Data<Elem = f64>>(array: &ArrayBase<T, D>, requires_grad: bool) -> Tensor<D, F>
+    where
+        Self: Sized;
+
+    fn values(&self) -> Array<F, D>;
+
+    /// Gradients of every element, zero where none has been computed.
+    fn grads(&self) -> Array<F, D>;
+
+    fn zero_grad(&self);
+
+    /// Overwrites the value of every element in place, leaving nodes already
+    /// computed from them untouched.
+    fn set_values<T: Data<Elem = F>>(&self, values: &ArrayBase<T, D>);
 }
 
-impl<S> DotProd<ArrayBase<S, Ix1>> for ArrayBase<S, Ix1>
+impl<S, D, F> TensorGrad<D, F> for ArrayBase<S, D>
 where
-    S: Data<Elem = Value>,
+    S: Data<Elem = Value<F>>,
+    D: Dimension,
+    F: Float,
 {
-    type Output = Value;
+    fn register_hook<H>(&self, hook: H)
+    where
+        H: Fn(&Value<F>) -> Option<Value<F>> + MaybeSync + 'static,
+    {
+        let hook = Shared::new(hook);
+        for value in self.iter() {
+            let hook = hook.clone();
+            value.register_hook(move |grad| hook(grad));
+        }
+    }
 
-    fn dot(&self, b: &ArrayBase<S, Ix1>) -> Self::Output {
-        let a = self.view();
-        let b = b.view();
+    fn retain_grad(&self) {
+        self.iter().for_each(Value::retain_grad);
+    }
+
+    fn detach(&self) -> Tensor<D, F> {
+        self.map(Value::detach)
+    }
+
+    fn tangents(&self) -> Array<F, D> {
+        self.map(|value| value.tangent().unwrap_or_else(F::zero))
+    }
+
+    fn from_f64<T: Data<Elem = f64>>(array: &ArrayBase<T, D>, requires_grad: bool) -> Tensor<D, F> {
+        array.mapv(|x| {
+            let mut value = Value::new(F::cast(x));
+            value.requires_grad(requires_grad);
+            value
+        })
+    }
+
+    fn values(&self) -> Array<F, D> {
+        self.map(Value::value)
+    }
+
+    fn grads(&self) -> Array<F, D> {
+        self.map(|value| value.grad().map_or_else(F::zero, |grad| grad.value()))
+    }
+
+    fn zero_grad(&self) {
+        self.iter().for_each(Value::zero_grad);
+    }
 
-        assert!(a.dim() == b.dim());
+    fn set_values<T: Data<Elem = F>>(&self, values: &ArrayBase<T, D>) {
+        assert!(
+            self.shape() == values.shape(),
+            "Cannot set values of shape {:?} on a tensor of shape {:?}",
+            values.shape(),
+            self.shape()
+        );
 
-        zip(a.to_vec(), b.to_vec()).map(|(x, y)| x * y).sum()
+        for (value, new_value) in zip(self, values) {
+            *value.value_mut() = *new_value;
+        }
     }
 }
 
-impl<S> DotProd<ArrayBase<S, Ix2>> for ArrayBase<S, Ix1>
+/// Differentiable reductions over any set of `axes`, which are kept with
+/// length one when `keepdim` is set so the result broadcasts back against the
+/// input. The output dimension is picked by the caller, e.g. `D` with
+/// `keepdim` or `IxDyn`, and panics if it does not match.
+///
+/// Named `*_axes` because ndarray's own `sum_axis` and `mean_axis` would
+/// shadow trait methods of the same name.
+pub trait TensorReduce<F: Float = f64> {
+    fn sum_axes<E: Dimension>(&self, axes: &[Axis], keepdim: bool) -> Tensor<E, F>;
+
+    fn mean_axes<E: Dimension>(&self, axes: &[Axis], keepdim: bool) -> Tensor<E, F>;
+
+    /// Divides by `n - correction`, so `1` gives the unbiased estimate.
+    fn var_axes<E: Dimension>(
+        &self,
+        axes: &[Axis],
+        correction: usize,
+        keepdim: bool,
+    ) -> Tensor<E, F>;
+
+    /// `log(sum(exp(x)))`, shifted by the maximum to stay finite.
+    fn logsumexp_axes<E: Dimension>(&self, axes: &[Axis], keepdim: bool) -> Tensor<E, F>;
+
+    /// Gradients only flow to the first maximum of each lane.
+    fn max_axes<E: Dimension>(&self, axes: &[Axis], keepdim: bool) -> Tensor<E, F>;
+
+    fn argmax<E: Dimension>(&self, axis: Axis, keepdim: bool) -> Array<usize, E>;
+
+    /// `p`-norm of every element taken together, with a gradient of zero at
+    /// the origin.
+    fn norm(&self, p: F) -> Value<F>;
+}
+
+impl<S, D, F> TensorReduce<F> for ArrayBase<S, D>
 where
-    S: Data<Elem = Value>,
+    S: Data<Elem = Value<F>>,
+    D: Dimension,
+    F: Float,
 {
-    type Output = Tensor<Ix1>;
+    fn sum_axes<E: Dimension>(&self, axes: &[Axis], keepdim: bool) -> Tensor<E, F> {
+        let sum = reduce(self.view().into_dyn(), axes, |lane| {
+            lane.iter().cloned().sum()
+        });
+        squeeze(sum, axes, keepdim)
+    }
 
-    fn dot(&self, b: &ArrayBase<S, Ix2>) -> Self::Output {
-        let a = self.view();
-        let b = b.view();
+    fn mean_axes<E: Dimension>(&self, axes: &[Axis], keepdim: bool) -> Tensor<E, F> {
+        let count = F::cast(reduced_len(self.shape(), axes) as f64);
+        let sum = self.sum_axes::<IxDyn>(axes, true);
+
+        squeeze(sum.mapv(|sum| sum / count), axes, keepdim)
+    }
+
+    fn var_axes<E: Dimension>(
+        &self,
+        axes: &[Axis],
+        correction: usize,
+        keepdim: bool,
+    ) -> Tensor<E, F> {
+        let count = reduced_len(self.shape(), axes);
+        assert!(
+            count > correction,
+            "Variance of {count} elements is undefined with correction {correction}"
+        );
+
+        let input = self.view().into_dyn();
+        let mean = input.mean_axes::<IxDyn>(axes, true);
+        let squared = (&input - &mean).mapv(|diff| diff.powf(F::cast(2.0)));
+        let sum = squared.sum_axes::<IxDyn>(axes, true);
+
+        let divisor = F::cast((count - correction) as f64);
+        squeeze(sum.mapv(|sum| sum / divisor), axes, keepdim)
+    }
+
+    fn logsumexp_axes<E: Dimension>(&self, axes: &[Axis], keepdim: bool) -> Tensor<E, F> {
+        let input = self.view().into_dyn();
+        let max = input.max_axes::<IxDyn>(axes, true).mapv(|max| max.detach());
+        let exp = (&input - &max).mapv(|shifted| shifted.exp());
+        let log = exp.sum_axes::<IxDyn>(axes, true).mapv(|sum| sum.log());
+
+        squeeze(log + max, axes, keepdim)
+    }
+
+    fn max_axes<E: Dimension>(&self, axes: &[Axis], keepdim: bool) -> Tensor<E, F> {
+        let max = reduce(self.view().into_dyn(), axes, |lane| {
+            lane.iter()
+                .cloned()
+                .reduce(|max, x| if x.value() > max.value() { x } else { max })
+                .expect("Cannot take the maximum of an empty axis")
+        });
+        squeeze(max, axes, keepdim)
+    }
 
-        let (n, _) = b.dim();
-        assert!(a.dim() == n);
+    fn argmax<E: Dimension>(&self, axis: Axis, keepdim: bool) -> Array<usize, E> {
+        let argmax = self.view().into_dyn().map_axis(axis, |lane| {
+            if lane.is_empty() {
+                panic!("Cannot take the maximum of an empty axis");
+            }
 
-        let a_t = a.t();
+            let mut argmax = 0;
+            for (idx, value) in lane.iter().enumerate() {
+                if value.value() > lane[argmax].value() {
+                    argmax = idx;
+                }
+            }
+            argmax
+        });
+        squeeze(argmax.insert_axis(axis), &[axis], keepdim)
+    }
 
-        let mut result = vec![];
-        for col in b.columns() {
-            result.push(a_t.dot(&col));
+    fn norm(&self, p: F) -> Value<F> {
+        // The chain rule gives NaN at the origin, so use the subgradient 0.
+        if self.iter().all(|x| x.value().is_zero()) {
+            return self.iter().map(|x| x * &F::zero()).sum();
         }
 
-        Tensor::<Ix1>::from_vec(result)
+        let sum = self.iter().map(|x| x.abs().powf(p)).sum::<Value<F>>();
+        sum.powf(F::one() / p)
+    }
+}
+
+fn check_axes(ndim: usize, axes: &[Axis]) {
+    for (idx, axis) in axes.iter().enumerate() {
+        assert!(
+            axis.index() < ndim && !axes[..idx].contains(axis),
+            "Cannot reduce over axes {axes:?} of a tensor with {ndim} axes"
+        );
     }
 }
 
-impl<S> DotProd<ArrayBase<S, Ix2>> for ArrayBase<S, Ix2>
+fn reduced_len(shape: &[usize], axes: &[Axis]) -> usize {
+    check_axes(shape.len(), axes);
+    axes.iter().map(|axis| shape[axis.index()]).product()
+}
+
+// Reduces one axis at a time, keeping each so the remaining indices stay put.
+fn reduce<F, R>(input: ArrayViewD<Value<F>>, axes: &[Axis], lane: R) -> Tensor<IxDyn, F>
 where
-    S: Data<Elem = Value>,
+    F: Float,
+    R: Fn(ArrayView1<Value<F>>) -> Value<F>,
 {
-    type Output = Tensor<Ix2>;
+    check_axes(input.ndim(), axes);
 
-    fn dot(&self, b: &ArrayBase<S, Ix2>) -> Self::Output {
-        let a = self.view();
-        let b = b.view();
-        let ((m, k), (k2, n)) = (a.dim(), b.dim());
-        if k != k2 || m.checked_mul(n).is_none() {
-            panic!("Could not multiply");
-        }
+    let mut reduced = input.to_owned();
+    for axis in axes {
+        reduced = reduced.map_axis(*axis, &lane).insert_axis(*axis);
+    }
 
-        let mut result = vec![];
+    reduced
+}
 
-        for row in a.rows() {
-            for col in b.columns() {
-                result.push(row.dot(&col));
-            }
+fn squeeze<A, E: Dimension>(mut reduced: ArrayD<A>, axes: &[Axis], keepdim: bool) -> Array<A, E> {
+    if !keepdim {
+        let mut axes = axes.to_vec();
+        axes.sort_by(|a, b| b.cmp(a));
+        for axis in axes {
+            reduced = reduced.index_axis_move(axis, 0);
         }
+    }
+
+    let ndim = reduced.ndim();
+    reduced.into_dimensionality().unwrap_or_else(|_| {
+        panic!("Reduction has {ndim} axes, which does not match the requested dimension")
+    })
+}
+
+pub trait DotProd<Rhs> {
+    type Output;
+
+    fn dot(&self, b: &Rhs) -> Self::Output;
+}
+
+impl<S, F> DotProd<ArrayBase<S, Ix1>> for ArrayBase<S, Ix1>
+where
+    S: Data<Elem = Value<F>>,
+    F: Float,
+{
+    type Output = Value<F>;
+
+    fn dot(&self, b: &ArrayBase<S, Ix1>) -> Self::Output {
+        let a = self.view().insert_axis(Axis(0));
+        let b = b.view().insert_axis(Axis(1));
+
+        matmul(a, b).into_iter().next().unwrap()
+    }
+}
+
+impl<S, F> DotProd<ArrayBase<S, Ix2>> for ArrayBase<S, Ix1>
+where
+    S: Data<Elem = Value<F>>,
+    F: Float,
+{
+    type Output = Tensor<Ix1, F>;
+
+    fn dot(&self, b: &ArrayBase<S, Ix2>) -> Self::Output {
+        let a = self.view().insert_axis(Axis(0));
+
+        matmul(a, b.view()).index_axis_move(Axis(0), 0)
+    }
+}
+
+impl<S, F> DotProd<ArrayBase<S, Ix2>> for ArrayBase<S, Ix2>
+where
+    S: Data<Elem = Value<F>>,
+    F: Float,
+{
+    type Output = Tensor<Ix2, F>;
 
-        Tensor::from_shape_vec((m, n), result).unwrap()
+    fn dot(&self, b: &ArrayBase<S, Ix2>) -> Self::Output {
+        matmul(self.view(), b.view())
     }
 }
 
-impl<S> DotProd<ArrayBase<S, Ix1>> for ArrayBase<S, Ix2>
+impl<S, F> DotProd<ArrayBase<S, Ix1>> for ArrayBase<S, Ix2>
 where
-    S: Data<Elem = Value>,
+    S: Data<Elem = Value<F>>,
+    F: Float,
 {
-    type Output = Tensor<Ix2>;
+    type Output = Tensor<Ix1, F>;
 
     fn dot(&self, b: &ArrayBase<S, Ix1>) -> Self::Output {
-        let a = self.view();
-        let b = b.view();
+        let b = b.view().insert_axis(Axis(1));
+
+        matmul(self.view(), b).index_axis_move(Axis(1), 0)
+    }
+}
 
-        let (m, k) = a.dim();
-        let n = b.dim();
+/// Batched matrix product, multiplying every matrix of `self` by `b`.
+impl<S, F> DotProd<ArrayBase<S, Ix2>> for ArrayBase<S, Ix3>
+where
+    S: Data<Elem = Value<F>>,
+    F: Float,
+{
+    type Output = Tensor<Ix3, F>;
+
+    fn dot(&self, b: &ArrayBase<S, Ix2>) -> Self::Output {
+        let (batch, m, _) = self.dim();
+        let n = b.ncols();
+
+        let result = self
+            .outer_iter()
+            .flat_map(|a| matmul(a, b.view()))
+            .collect();
+
+        Tensor::from_shape_vec((batch, m, n), result).unwrap()
+    }
+}
 
-        if k != 1 || m.checked_mul(n).is_none() {
-            panic!("Could not multiply");
+/// Batched matrix product, multiplying the matrices of `self` and `b` pairwise.
+impl<S, F> DotProd<ArrayBase<S, Ix3>> for ArrayBase<S, Ix3>
+where
+    S: Data<Elem = Value<F>>,
+    F: Float,
+{
+    type Output = Tensor<Ix3, F>;
+
+    fn dot(&self, b: &ArrayBase<S, Ix3>) -> Self::Output {
+        let ((batch, m, _), (batch2, _, n)) = (self.dim(), b.dim());
+        if batch != batch2 {
+            panic!(
+                "Could not multiply batches of shape {:?} and {:?}",
+                self.dim(),
+                b.dim()
+            );
         }
 
-        let b_elems = b.to_vec();
-        let mut result = vec![];
+        let result = zip(self.outer_iter(), b.outer_iter())
+            .flat_map(|(a, b)| matmul(a, b))
+            .collect();
 
-        for row in a.rows() {
-            let x = row.get(0).unwrap();
-            for elem in b_elems.iter() {
-                result.push(elem * x);
-            }
+        Tensor::from_shape_vec((batch, m, n), result).unwrap()
+    }
+}
+
+/// Matrix product recorded as a single [`MatMulOps`] product node, whose
+/// elements are backpropagated together. On the tape each element is one
+/// node with an edge to every operand it was computed from.
+pub(crate) fn matmul<F: Float>(a: ArrayView2<Value<F>>, b: ArrayView2<Value<F>>) -> Tensor<Ix2, F> {
+    let ((m, k), (k2, n)) = (a.dim(), b.dim());
+    if k != k2 || m.checked_mul(n).is_none() {
+        panic!("Could not multiply");
+    }
+
+    let (a_values, b_values) = (a.values(), b.values());
+    let values = Array2::from_shape_fn((m, n), |(i, j)| {
+        zip(a_values.row(i), b_values.column(j))
+            .map(|(x, y)| *x * *y)
+            .sum::<F>()
+    });
+
+    #[cfg(feature = "tape")]
+    if is_recording() && is_grad_enabled() {
+        profiler::record_op(|| String::from("MatMul"));
+        let elements = tape::push_matmul(a, b, &values);
+        return elements.mapv(|element| {
+            profiler::record_op(|| String::from("MatMulElement"));
+            Value::from_tape(element)
+        });
+    }
+
+    let result = if is_grad_enabled() {
+        let product = Value::with_op(
+            F::zero(),
+            MatMulOps::Product {
+                lhs: a.iter().cloned().collect(),
+                rhs: b.iter().cloned().collect(),
+                dims: (m, k, n),
+            },
+        );
+
+        Tensor::from_shape_fn((m, n), |(i, j)| {
+            let element = MatMulOps::Element(product.clone(), i * n + j);
+            Value::with_op(values[(i, j)], element)
+        })
+    } else {
+        values.mapv(Value::constant)
+    };
+
+    if a.iter().chain(b.iter()).any(|x| x.tangent().is_some()) {
+        let tangent = |x: &Value<F>| x.tangent().unwrap_or_else(F::zero);
+        let tangents = a.map(tangent).dot(&b_values) + a_values.dot(&b.map(tangent));
+        for (value, tangent) in zip(&result, tangents) {
+            value.set_tangent(Some(tangent));
         }
+    }
+
+    result
+}
+
+/// Outer product of two vectors, `a[i] * b[j]` at `[i, j]`.
+pub fn outer<S, F>(a: &ArrayBase<S, Ix1>, b: &ArrayBase<S, Ix1>) -> Tensor<Ix2, F>
+where
+    S: Data<Elem = Value<F>>,
+    F: Float,
+{
+    let (m, n) = (a.len(), b.len());
 
-        Tensor::from_shape_vec((m, n), result).unwrap()
+    let mut result = vec![];
+    for x in a {
+        for y in b {
+            result.push(x * y);
+        }
     }
+
+    Tensor::from_shape_vec((m, n), result).unwrap()
 }
@@ -0,0 +1,503 @@
+mod ops;
+
+use self::ops::{Bridge, TensorOp};
+use crate::autograd::is_grad_enabled;
+use crate::float::Float;
+use crate::prelude::*;
+use crate::shared::{Lock, Shared};
+use crate::value::NodeId;
+
+use ndarray::{ArrayD, IxDyn, RemoveAxis};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+pub(crate) type TensorNode<F> = Shared<Lock<TensorData<F>>>;
+
+pub(crate) struct TensorData<F: Float> {
+    value: ArrayD<F>,
+    grad: Option<ArrayD<F>>,
+    operation: TensorOp<F>,
+    requires_grad: bool,
+}
+
+/// A whole array recorded as a single graph node. Matrix products,
+/// elementwise ops and reductions each add one node with a vectorized
+/// backward, instead of one `Value` per element.
+///
+/// Layers run on it through [`Layer::forward_value`](crate::Layer::forward_value),
+/// and gradients flow back into the `Value`s of their parameters.
+pub struct TensorValue<D, F: Float = f64> {
+    node: TensorNode<F>,
+    dim: PhantomData<D>,
+}
+
+impl<D: Dimension, F: Float> TensorValue<D, F> {
+    pub fn new(value: Array<F, D>) -> Self {
+        leaf(value.into_dyn(), true)
+    }
+
+    pub fn constant(value: Array<F, D>) -> Self {
+        leaf(value.into_dyn(), false)
+    }
+
+    /// Gathers `tensor` into a single node. Gradients reaching it are passed
+    /// on to the graph of every element.
+    pub fn from_tensor(tensor: &Tensor<D, F>) -> Self {
+        let value = tensor.mapv(|value| value.value()).into_dyn();
+        let bridge = Bridge {
+            input: None,
+            leaves: vec![],
+            outputs: tensor.iter().cloned().collect(),
+        };
+
+        with_op(value, TensorOp::Bridge(bridge))
+    }
+
+    /// Runs the scalar graph built by `f` as a single node, e.g. to apply a
+    /// layer that has no tensor-level forward.
+    pub fn apply<E, G>(&self, f: G) -> TensorValue<E, F>
+    where
+        E: Dimension,
+        G: FnOnce(&Tensor<D, F>) -> Tensor<E, F>,
+    {
+        let requires_grad = self.should_compute_grad();
+        let input = self.value().mapv(|value| {
+            let mut leaf = Value::new(value);
+            leaf.requires_grad(requires_grad);
+            leaf
+        });
+
+        let output = f(&input);
+        let bridge = Bridge {
+            input: Some(self.node.clone()),
+            leaves: input.into_iter().collect(),
+            outputs: output.iter().cloned().collect(),
+        };
+
+        let value = output.mapv(|value| value.value()).into_dyn();
+        with_op(value, TensorOp::Bridge(bridge))
+    }
+
+    pub fn value(&self) -> Array<F, D> {
+        let value = self.node.borrow().value.clone();
+        value.into_dimensionality().unwrap()
+    }
+
+    pub fn grad(&self) -> Option<Array<F, D>> {
+        let grad = self.node.borrow().grad.clone();
+        grad.map(|grad| grad.into_dimensionality().unwrap())
+    }
+
+    pub fn zero_grad(&self) {
+        self.node.borrow_mut().grad = None;
+    }
+
+    pub fn requires_grad(&mut self, requires: bool) {
+        self.node.borrow_mut().requires_grad = requires;
+    }
+
+    pub fn should_compute_grad(&self) -> bool {
+        self.node.borrow().requires_grad
+    }
+
+    pub fn is_leaf(&self) -> bool {
+        matches!(self.node.borrow().operation, TensorOp::Leaf)
+    }
+
+    pub fn detach(&self) -> Self {
+        TensorValue::constant(self.value())
+    }
+
+    pub fn shape(&self) -> Vec<usize> {
+        self.node.borrow().value.shape().to_vec()
+    }
+
+    pub fn len(&self) -> usize {
+        self.node.borrow().value.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn into_dyn(self) -> TensorValue<IxDyn, F> {
+        TensorValue {
+            node: self.node,
+            dim: PhantomData,
+        }
+    }
+
+    pub fn into_dimensionality<E: Dimension>(self) -> TensorValue<E, F> {
+        let ndim = self.node.borrow().value.ndim();
+        assert!(
+            E::NDIM.is_none_or(|expected| expected == ndim),
+            "Cannot view a tensor value of rank {ndim} as rank {:?}",
+            E::NDIM
+        );
+
+        TensorValue {
+            node: self.node,
+            dim: PhantomData,
+        }
+    }
+
+    /// Reverses the order of the axes, i.e. the transpose of a matrix.
+    pub fn t(&self) -> Self {
+        let value = self.node.borrow().value.t().to_owned();
+        with_op(value, TensorOp::Transpose(self.node.clone()))
+    }
+
+    pub fn powf(&self, exponent: F) -> Self {
+        let value = self.node.borrow().value.mapv(|x| x.powf(exponent));
+        with_op(value, TensorOp::Powf(self.node.clone(), exponent))
+    }
+
+    pub fn exp(&self) -> Self {
+        let value = self.node.borrow().value.mapv(F::exp);
+        with_op(value, TensorOp::Exp(self.node.clone()))
+    }
+
+    pub fn log(&self) -> Self {
+        let value = self.node.borrow().value.mapv(F::ln);
+        with_op(value, TensorOp::Log(self.node.clone()))
+    }
+
+    pub fn tanh(&self) -> Self {
+        let value = self.node.borrow().value.mapv(F::tanh);
+        with_op(value, TensorOp::Tanh(self.node.clone()))
+    }
+
+    pub fn sigmoid(&self) -> Self {
+        let value = self
+            .node
+            .borrow()
+            .value
+            .mapv(|x| F::one() / (F::one() + (-x).exp()));
+        with_op(value, TensorOp::Sigmoid(self.node.clone()))
+    }
+
+    pub fn relu(&self) -> Self {
+        let value = self.node.borrow().value.mapv(|x| x.max(F::zero()));
+        with_op(value, TensorOp::ReLU(self.node.clone()))
+    }
+
+    pub fn sum(&self) -> TensorValue<Ix0, F> {
+        let value = arr0(self.node.borrow().value.sum()).into_dyn();
+        with_op(value, TensorOp::Sum(self.node.clone()))
+    }
+
+    pub fn mean(&self) -> TensorValue<Ix0, F> {
+        self.sum() / F::cast(self.len() as f64)
+    }
+
+    pub fn sum_axis(&self, axis: Axis) -> TensorValue<D::Smaller, F>
+    where
+        D: RemoveAxis,
+    {
+        let value = self.node.borrow().value.sum_axis(axis);
+        with_op(value, TensorOp::SumAxis(self.node.clone(), axis))
+    }
+
+    pub fn mean_axis(&self, axis: Axis) -> TensorValue<D::Smaller, F>
+    where
+        D: RemoveAxis,
+    {
+        let len = self.node.borrow().value.len_of(axis);
+        self.sum_axis(axis) / F::cast(len as f64)
+    }
+
+    /// Backpropagates from a single element tensor value, accumulating into
+    /// the gradients of its leaves and of any `Value` it was gathered from.
+    pub fn backward(&self) {
+        assert!(
+            self.len() == 1,
+            "Backward can only start from a single element, got shape {:?}",
+            self.shape()
+        );
+
+        let topo_order = topo_sort(&self.node);
+        let mut grads: HashMap<NodeId, ArrayD<F>> = HashMap::new();
+        grads.insert(node_id(&self.node), ArrayD::ones(self.shape()));
+
+        for node in topo_order.iter().rev() {
+            let Some(grad) = grads.remove(&node_id(node)) else {
+                continue;
+            };
+
+            let data = node.borrow();
+            if let TensorOp::Leaf = data.operation {
+                drop(data);
+                let mut data = node.borrow_mut();
+                if !data.requires_grad {
+                    continue;
+                }
+                data.grad = Some(match data.grad.take() {
+                    Some(prev) => prev + grad,
+                    None => grad,
+                });
+                continue;
+            }
+
+            let operand_grads = data.operation.backward(&data.value, &grad);
+            for (operand, operand_grad) in data.operation.variables().iter().zip(operand_grads) {
+                if !operand.borrow().requires_grad {
+                    continue;
+                }
+
+                let id = node_id(operand);
+                let operand_grad = match grads.remove(&id) {
+                    Some(prev) => prev + operand_grad,
+                    None => operand_grad,
+                };
+                grads.insert(id, operand_grad);
+            }
+        }
+    }
+}
+
+fn leaf<D, F: Float>(value: ArrayD<F>, requires_grad: bool) -> TensorValue<D, F> {
+    let data = TensorData {
+        value,
+        grad: None,
+        operation: TensorOp::Leaf,
+        requires_grad,
+    };
+
+    TensorValue {
+        node: Shared::new(Lock::new(data)),
+        dim: PhantomData,
+    }
+}
+
+fn with_op<D: Dimension, F: Float>(value: ArrayD<F>, operation: TensorOp<F>) -> TensorValue<D, F> {
+    if let Some(ndim) = D::NDIM {
+        assert!(
+            value.ndim() == ndim,
+            "Expected a result of rank {ndim}, got rank {}",
+            value.ndim()
+        );
+    }
+
+    let requires_grad = is_grad_enabled()
+        && match &operation {
+            TensorOp::Bridge(bridge) => bridge
+                .outputs
+                .iter()
+                .any(|output| output.should_compute_grad()),
+            operation => operation
+                .variables()
+                .iter()
+                .any(|operand| operand.borrow().requires_grad),
+        };
+    if !requires_grad {
+        return leaf(value, false);
+    }
+
+    let data = TensorData {
+        value,
+        grad: None,
+        operation,
+        requires_grad,
+    };
+
+    TensorValue {
+        node: Shared::new(Lock::new(data)),
+        dim: PhantomData,
+    }
+}
+
+fn node_id<F: Float>(node: &TensorNode<F>) -> NodeId {
+    Shared::as_ptr(node) as NodeId
+}
+
+fn topo_sort<F: Float>(root: &TensorNode<F>) -> Vec<TensorNode<F>> {
+    let mut order = vec![];
+    let mut visited = HashSet::new();
+    let mut stack = vec![(root.clone(), false)];
+
+    while let Some((node, expanded)) = stack.pop() {
+        if expanded {
+            order.push(node);
+            continue;
+        }
+
+        if !visited.insert(node_id(&node)) {
+            continue;
+        }
+
+        let operands = node.borrow().operation.variables();
+        stack.push((node, true));
+        stack.extend(
+            operands
+                .into_iter()
+                .filter(|operand| !visited.contains(&node_id(operand)))
+                .map(|operand| (operand, false)),
+        );
+    }
+
+    order
+}
+
+impl<D, F: Float> Clone for TensorValue<D, F> {
+    fn clone(&self) -> Self {
+        TensorValue {
+            node: self.node.clone(),
+            dim: PhantomData,
+        }
+    }
+}
+
+impl<D: Dimension, F: Float> fmt::Debug for TensorValue<D, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let data = self.node.borrow();
+        write!(
+            f,
+            "TensorValue(data: {:?}, grad: {:?})",
+            data.value, data.grad
+        )
+    }
+}
+
+impl<F: Float> DotProd<TensorValue<Ix1, F>> for TensorValue<Ix1, F> {
+    type Output = TensorValue<Ix0, F>;
+
+    fn dot(&self, b: &TensorValue<Ix1, F>) -> Self::Output {
+        self.matmul(b)
+    }
+}
+
+impl<F: Float> DotProd<TensorValue<Ix2, F>> for TensorValue<Ix1, F> {
+    type Output = TensorValue<Ix1, F>;
+
+    fn dot(&self, b: &TensorValue<Ix2, F>) -> Self::Output {
+        self.matmul(b)
+    }
+}
+
+impl<F: Float> DotProd<TensorValue<Ix1, F>> for TensorValue<Ix2, F> {
+    type Output = TensorValue<Ix1, F>;
+
+    fn dot(&self, b: &TensorValue<Ix1, F>) -> Self::Output {
+        self.matmul(b)
+    }
+}
+
+impl<F: Float> DotProd<TensorValue<Ix2, F>> for TensorValue<Ix2, F> {
+    type Output = TensorValue<Ix2, F>;
+
+    fn dot(&self, b: &TensorValue<Ix2, F>) -> Self::Output {
+        self.matmul(b)
+    }
+}
+
+impl<F: Float> DotProd<TensorValue<Ix2, F>> for TensorValue<Ix3, F> {
+    type Output = TensorValue<Ix3, F>;
+
+    fn dot(&self, b: &TensorValue<Ix2, F>) -> Self::Output {
+        self.matmul(b)
+    }
+}
+
+impl<F: Float> DotProd<TensorValue<Ix3, F>> for TensorValue<Ix3, F> {
+    type Output = TensorValue<Ix3, F>;
+
+    fn dot(&self, b: &TensorValue<Ix3, F>) -> Self::Output {
+        self.matmul(b)
+    }
+}
+
+impl<D: Dimension, F: Float> TensorValue<D, F> {
+    fn matmul<E: Dimension, O: Dimension>(&self, b: &TensorValue<E, F>) -> TensorValue<O, F> {
+        let value = ops::matmul(&self.node.borrow().value, &b.node.borrow().value);
+        with_op(value, TensorOp::MatMul(self.node.clone(), b.node.clone()))
+    }
+}
+
+impl<D: Dimension, F: Float> Neg for &TensorValue<D, F> {
+    type Output = TensorValue<D, F>;
+
+    fn neg(self) -> Self::Output {
+        self * -F::one()
+    }
+}
+
+impl<D: Dimension, F: Float> Neg for TensorValue<D, F> {
+    type Output = TensorValue<D, F>;
+
+    fn neg(self) -> Self::Output {
+        -&self
+    }
+}
+
+/// Dimensions that broadcast into `D` without adding axes, i.e. those of
+/// the same or a lower rank. Binary ops are only implemented for right
+/// operands that broadcast into the dimension of the left one.
+pub trait BroadcastInto<D: Dimension>: Dimension {}
+
+impl<D: Dimension> BroadcastInto<D> for Ix0 {}
+
+macro_rules! impl_broadcast_into {
+    ($dim: ty => $($larger: ty),*) => {
+        $( impl BroadcastInto<$larger> for $dim {} )*
+    };
+}
+
+impl_broadcast_into!(Ix1 => Ix1, Ix2, Ix3, Ix4, Ix5, Ix6, IxDyn);
+impl_broadcast_into!(Ix2 => Ix2, Ix3, Ix4, Ix5, Ix6, IxDyn);
+impl_broadcast_into!(Ix3 => Ix3, Ix4, Ix5, Ix6, IxDyn);
+impl_broadcast_into!(Ix4 => Ix4, Ix5, Ix6, IxDyn);
+impl_broadcast_into!(Ix5 => Ix5, Ix6, IxDyn);
+impl_broadcast_into!(Ix6 => Ix6, IxDyn);
+impl_broadcast_into!(IxDyn => IxDyn);
+
+// The result keeps the dimension of the left operand, which the right one
+// is broadcast to.
+macro_rules! impl_binary_ops {
+    ($trait: ident, $mth: ident, $operator: tt, $op_varient: tt) => {
+        impl<'a, D: Dimension, E: BroadcastInto<D>, F: Float> $trait<&'a TensorValue<E, F>>
+            for &'a TensorValue<D, F>
+        {
+            type Output = TensorValue<D, F>;
+
+            fn $mth(self, rhs: &'a TensorValue<E, F>) -> Self::Output {
+                let value = &self.node.borrow().value $operator &rhs.node.borrow().value;
+                let operation = TensorOp::$op_varient(self.node.clone(), rhs.node.clone());
+
+                with_op(value, operation)
+            }
+        }
+
+        impl<D: Dimension, E: BroadcastInto<D>, F: Float> $trait<TensorValue<E, F>>
+            for TensorValue<D, F>
+        {
+            type Output = TensorValue<D, F>;
+
+            fn $mth(self, rhs: TensorValue<E, F>) -> Self::Output {
+                &self $operator &rhs
+            }
+        }
+
+        impl<D: Dimension, F: Float> $trait<F> for &TensorValue<D, F> {
+            type Output = TensorValue<D, F>;
+
+            fn $mth(self, rhs: F) -> Self::Output {
+                self $operator &TensorValue::<Ix0, F>::constant(arr0(rhs))
+            }
+        }
+
+        impl<D: Dimension, F: Float> $trait<F> for TensorValue<D, F> {
+            type Output = TensorValue<D, F>;
+
+            fn $mth(self, rhs: F) -> Self::Output {
+                &self $operator rhs
+            }
+        }
+    };
+}
+
+impl_binary_ops!(Add, add, +, Add);
+impl_binary_ops!(Sub, sub, -, Sub);
+impl_binary_ops!(Mul, mul, *, Mul);
+impl_binary_ops!(Div, div, /, Div);
@@ -0,0 +1,272 @@
+use ndarray::{Array2, ArrayD, ArrayView2, Axis, CowArray, Ix2, IxDyn, Zip};
+
+use super::TensorNode;
+use crate::autograd::engine::{self, Grad};
+use crate::float::Float;
+use crate::value::Value;
+
+#[derive(Default)]
+pub(crate) enum TensorOp<F: Float> {
+    #[default]
+    Leaf,
+    Add(TensorNode<F>, TensorNode<F>),
+    Sub(TensorNode<F>, TensorNode<F>),
+    Mul(TensorNode<F>, TensorNode<F>),
+    Div(TensorNode<F>, TensorNode<F>),
+    MatMul(TensorNode<F>, TensorNode<F>),
+    Transpose(TensorNode<F>),
+    Powf(TensorNode<F>, F),
+    Exp(TensorNode<F>),
+    Log(TensorNode<F>),
+    Tanh(TensorNode<F>),
+    Sigmoid(TensorNode<F>),
+    ReLU(TensorNode<F>),
+    Sum(TensorNode<F>),
+    SumAxis(TensorNode<F>, Axis),
+    Bridge(Bridge<F>),
+}
+
+// Scalar graph spliced into the tensor graph: `outputs` were computed from
+// `leaves`, which mirror the elements of `input`.
+pub(crate) struct Bridge<F: Float> {
+    pub(crate) input: Option<TensorNode<F>>,
+    pub(crate) leaves: Vec<Value<F>>,
+    pub(crate) outputs: Vec<Value<F>>,
+}
+
+impl<F: Float> TensorOp<F> {
+    pub(crate) fn variables(&self) -> Vec<TensorNode<F>> {
+        match self {
+            TensorOp::Leaf => vec![],
+            TensorOp::Add(a, b)
+            | TensorOp::Sub(a, b)
+            | TensorOp::Mul(a, b)
+            | TensorOp::Div(a, b)
+            | TensorOp::MatMul(a, b) => vec![a.clone(), b.clone()],
+            TensorOp::Transpose(a)
+            | TensorOp::Powf(a, _)
+            | TensorOp::Exp(a)
+            | TensorOp::Log(a)
+            | TensorOp::Tanh(a)
+            | TensorOp::Sigmoid(a)
+            | TensorOp::ReLU(a)
+            | TensorOp::Sum(a)
+            | TensorOp::SumAxis(a, _) => vec![a.clone()],
+            TensorOp::Bridge(bridge) => bridge.input.iter().cloned().collect(),
+        }
+    }
+
+    /// Gradients of every operand in `variables` order, given the value of the
+    /// node and the gradient it received.
+    pub(crate) fn backward(&self, value: &ArrayD<F>, grad: &ArrayD<F>) -> Vec<ArrayD<F>> {
+        match self {
+            TensorOp::Leaf => vec![],
+            TensorOp::Add(a, b) => vec![unbroadcast(grad, a), unbroadcast(grad, b)],
+            TensorOp::Sub(a, b) => vec![unbroadcast(grad, a), unbroadcast(&grad.mapv(|g| -g), b)],
+            TensorOp::Mul(a, b) => {
+                let (a_val, b_val) = (a.borrow().value.clone(), b.borrow().value.clone());
+                vec![
+                    unbroadcast(&(grad * &b_val), a),
+                    unbroadcast(&(grad * &a_val), b),
+                ]
+            }
+            TensorOp::Div(a, b) => {
+                let b_val = b.borrow().value.clone();
+                vec![
+                    unbroadcast(&(grad / &b_val), a),
+                    unbroadcast(&(grad * value / &b_val).mapv(|g| -g), b),
+                ]
+            }
+            TensorOp::MatMul(a, b) => {
+                let (a, b) = (a.borrow(), b.borrow());
+                let (a_grad, b_grad) = match b.value.ndim() {
+                    3 => batched_matmul_grads(&a.value, &b.value, grad),
+                    _ => matmul_grads(&a.value, &b.value, grad),
+                };
+
+                vec![a_grad, b_grad]
+            }
+            TensorOp::Transpose(_) => vec![grad.t().to_owned()],
+            TensorOp::Powf(a, exponent) => {
+                let exponent = *exponent;
+                let partial = a
+                    .borrow()
+                    .value
+                    .mapv(|x| exponent * x.powf(exponent - F::one()));
+                vec![grad * &partial]
+            }
+            TensorOp::Exp(_) => vec![grad * value],
+            TensorOp::Log(a) => vec![grad / &a.borrow().value],
+            TensorOp::Tanh(_) => vec![grad * &value.mapv(|y| F::one() - y * y)],
+            TensorOp::Sigmoid(_) => vec![grad * &value.mapv(|y| y * (F::one() - y))],
+            TensorOp::ReLU(a) => {
+                let mut a_grad = grad.clone();
+                Zip::from(&mut a_grad)
+                    .and(&a.borrow().value)
+                    .for_each(|g, &x| {
+                        if x <= F::zero() {
+                            *g = F::zero();
+                        }
+                    });
+                vec![a_grad]
+            }
+            TensorOp::Sum(a) => {
+                let shape = a.borrow().value.raw_dim();
+                vec![grad.broadcast(shape).unwrap().to_owned()]
+            }
+            TensorOp::SumAxis(a, axis) => {
+                let shape = a.borrow().value.raw_dim();
+                let grad = grad.view().insert_axis(*axis);
+                vec![grad.broadcast(shape).unwrap().to_owned()]
+            }
+            TensorOp::Bridge(bridge) => bridge.backward(grad).into_iter().collect(),
+        }
+    }
+}
+
+impl<F: Float> Bridge<F> {
+    // Runs the scalar engine from the outputs, so values behind the bridge
+    // accumulate their gradients as usual, and gathers the gradients of the
+    // leaves back into a single array for `input`.
+    fn backward(&self, grad: &ArrayD<F>) -> Option<ArrayD<F>> {
+        let (outputs, seeds) = self
+            .outputs
+            .iter()
+            .zip(grad.iter())
+            .filter(|(output, _)| output.should_compute_grad())
+            .map(|(output, grad)| (output.clone(), Grad::Raw(*grad)))
+            .unzip::<_, _, Vec<Value<F>>, Vec<Grad<F>>>();
+
+        let (topo_order, grads) = engine::execute_from(&outputs, seeds);
+
+        let input_grad = self.input.as_ref().map(|input| {
+            let leaf_grads = self
+                .leaves
+                .iter()
+                .map(|leaf| match grads.get(leaf) {
+                    Some(Grad::Raw(grad)) => grad,
+                    Some(Grad::Graph(grad)) => grad.value(),
+                    None => F::zero(),
+                })
+                .collect();
+            ArrayD::from_shape_vec(input.borrow().value.raw_dim(), leaf_grads).unwrap()
+        });
+
+        Value::apply_grads(topo_order, grads);
+        input_grad
+    }
+}
+
+/// Matrix product of two arrays of rank one to three, where a vector on
+/// the left acts as a row and a vector on the right as a column. A rank three
+/// array on the left is a batch of matrices, multiplied either by the same
+/// right operand or pairwise by a batch on the right.
+pub(crate) fn matmul<F: Float>(a: &ArrayD<F>, b: &ArrayD<F>) -> ArrayD<F> {
+    if b.ndim() == 3 {
+        let (batch, m, n) = batched_dims(a, b);
+        let mut product = ArrayD::zeros(IxDyn(&[batch, m, n]));
+
+        for (idx, mut out) in product.outer_iter_mut().enumerate() {
+            let (a, b) = (matrix_at(a, idx), matrix_at(b, idx));
+            out.assign(&a.dot(&b));
+        }
+        return product;
+    }
+
+    let product = as_matrix(a, true).dot(&as_matrix(b, false));
+
+    let mut shape = a.shape()[..a.ndim() - 1].to_vec();
+    shape.extend(&b.shape()[1..]);
+    reshape(product, &shape)
+}
+
+fn matmul_grads<F: Float>(
+    a: &ArrayD<F>,
+    b: &ArrayD<F>,
+    grad: &ArrayD<F>,
+) -> (ArrayD<F>, ArrayD<F>) {
+    let (a_mat, b_mat) = (as_matrix(a, true), as_matrix(b, false));
+    let grad = grad
+        .to_shape((a_mat.nrows(), b_mat.ncols()))
+        .unwrap()
+        .into_dimensionality::<Ix2>()
+        .unwrap();
+
+    (
+        reshape(grad.dot(&b_mat.t()), a.shape()),
+        reshape(a_mat.t().dot(&grad), b.shape()),
+    )
+}
+
+fn batched_matmul_grads<F: Float>(
+    a: &ArrayD<F>,
+    b: &ArrayD<F>,
+    grad: &ArrayD<F>,
+) -> (ArrayD<F>, ArrayD<F>) {
+    let mut a_grad = ArrayD::zeros(a.raw_dim());
+    let mut b_grad = ArrayD::zeros(b.raw_dim());
+
+    for idx in 0..grad.len_of(Axis(0)) {
+        let (a, b, grad) = (matrix_at(a, idx), matrix_at(b, idx), matrix_at(grad, idx));
+        a_grad
+            .index_axis_mut(Axis(0), idx)
+            .assign(&grad.dot(&b.t()));
+        b_grad
+            .index_axis_mut(Axis(0), idx)
+            .assign(&a.t().dot(&grad));
+    }
+
+    (a_grad, b_grad)
+}
+
+fn batched_dims<F: Float>(a: &ArrayD<F>, b: &ArrayD<F>) -> (usize, usize, usize) {
+    match (a.shape(), b.shape()) {
+        (&[batch, m, _], &[batch2, _, n]) if batch == batch2 => (batch, m, n),
+        (a, b) => panic!("Could not multiply batches of shape {a:?} and {b:?}"),
+    }
+}
+
+fn matrix_at<F: Float>(array: &ArrayD<F>, idx: usize) -> ArrayView2<'_, F> {
+    array
+        .index_axis(Axis(0), idx)
+        .into_dimensionality::<Ix2>()
+        .unwrap()
+}
+
+// Rank three operands on the left are flattened into one tall matrix.
+fn as_matrix<F: Float>(array: &ArrayD<F>, is_lhs: bool) -> CowArray<'_, F, Ix2> {
+    let matrix = match array.ndim() {
+        1 if is_lhs => array.view().insert_axis(Axis(0)),
+        1 => array.view().insert_axis(Axis(1)),
+        2 => array.view(),
+        3 if is_lhs => {
+            let shape = array.shape();
+            return array.to_shape((shape[0] * shape[1], shape[2])).unwrap();
+        }
+        ndim => panic!("Could not multiply an operand of rank {ndim}"),
+    };
+
+    matrix.into_dimensionality::<Ix2>().unwrap().into()
+}
+
+// Products of column major operands come out column major, so this may copy.
+fn reshape<F: Float>(array: Array2<F>, shape: &[usize]) -> ArrayD<F> {
+    array.to_shape(IxDyn(shape)).unwrap().into_owned()
+}
+
+// Sums a broadcast gradient back down to the shape of `operand`.
+fn unbroadcast<F: Float>(grad: &ArrayD<F>, operand: &TensorNode<F>) -> ArrayD<F> {
+    let shape = operand.borrow().value.shape().to_vec();
+    let mut grad = grad.clone();
+
+    while grad.ndim() > shape.len() {
+        grad = grad.sum_axis(Axis(0));
+    }
+    for (axis, &len) in shape.iter().enumerate() {
+        if len == 1 && grad.len_of(Axis(axis)) != 1 {
+            grad = grad.sum_axis(Axis(axis)).insert_axis(Axis(axis));
+        }
+    }
+
+    grad
+}
@@ -1,4 +1,4 @@
-use crate::{val, Value};
+use crate::{Float, Value};
 use rand::distributions::Uniform;
 use rand_distr::{Distribution, Normal};
 
@@ -8,7 +8,7 @@ pub struct GlorotNormal;
 pub struct GlorotUniform;
 
 impl WeightInit for GlorotNormal {
-    fn sample<F: Into<Fanning>>(&self, fanning: F) -> crate::Value {
+    fn sample<T: Float, F: Into<Fanning>>(&self, fanning: F) -> Value<T> {
         let Fanning(fan_in, fan_out) = fanning.into();
         let stdev = (2.0 / (fan_in as f64 + fan_out as f64)).sqrt();
         let normal = Normal::new(0.0, stdev).unwrap();
@@ -16,12 +16,12 @@ impl WeightInit for GlorotNormal {
         let mut rng = rand::thread_rng();
 
         let weight = normal.sample(&mut rng);
-        val!(weight)
+        Value::new(T::cast(weight))
     }
 }
 
 impl WeightInit for GlorotUniform {
-    fn sample<F: Into<Fanning>>(&self, fanning: F) -> crate::Value {
+    fn sample<T: Float, F: Into<Fanning>>(&self, fanning: F) -> Value<T> {
         let Fanning(fan_in, fan_out) = fanning.into();
         let limit = (6.0 / (fan_in as f64 + fan_out as f64)).sqrt();
         let uniform = Uniform::new(-limit, limit);
@@ -29,6 +29,6 @@ impl WeightInit for GlorotUniform {
         let mut rng = rand::thread_rng();
 
         let weight = uniform.sample(&mut rng);
-        val!(weight)
+        Value::new(T::cast(weight))
     }
 }
@@ -1,4 +1,4 @@
-use crate::{val, Value};
+use crate::{Float, Value};
 use rand::distributions::Uniform;
 use rand_distr::{Distribution, Normal};
 
@@ -8,7 +8,7 @@ pub struct LecunNormal;
 pub struct LecunUniform;
 
 impl WeightInit for LecunNormal {
-    fn sample<F: Into<Fanning>>(&self, fanning: F) -> crate::Value {
+    fn sample<T: Float, F: Into<Fanning>>(&self, fanning: F) -> Value<T> {
         let Fanning(fan_in, _) = fanning.into();
         let stdev = (1.0 / fan_in as f64).sqrt();
         let normal = Normal::new(0.0, stdev).unwrap();
@@ -16,12 +16,12 @@ impl WeightInit for LecunNormal {
         let mut rng = rand::thread_rng();
 
         let weight = normal.sample(&mut rng);
-        val!(weight)
+        Value::new(T::cast(weight))
     }
 }
 
 impl WeightInit for LecunUniform {
-    fn sample<F: Into<Fanning>>(&self, fanning: F) -> crate::Value {
+    fn sample<T: Float, F: Into<Fanning>>(&self, fanning: F) -> Value<T> {
         let Fanning(fan_in, _) = fanning.into();
         let limit = (3.0 / fan_in as f64).sqrt();
         let uniform = Uniform::new(-limit, limit);
@@ -29,6 +29,6 @@ impl WeightInit for LecunUniform {
         let mut rng = rand::thread_rng();
 
         let weight = uniform.sample(&mut rng);
-        val!(weight)
+        Value::new(T::cast(weight))
     }
 }
@@ -1,4 +1,4 @@
-use crate::Value;
+use crate::{Float, Value};
 
 mod glorot;
 mod he;
@@ -22,5 +22,5 @@ impl From<[usize; 1]> for Fanning {
 }
 
 pub trait WeightInit {
-    fn sample<F: Into<Fanning>>(&self, fanning: F) -> Value;
+    fn sample<T: Float, F: Into<Fanning>>(&self, fanning: F) -> Value<T>;
 }
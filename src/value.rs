@@ -1,14 +1,18 @@
-use super::ops::{BinaryOps, Op, Ops, UnaryOps};
+use super::autograd::engine::{self, Grad, GradTable};
+use super::autograd::profiler::{self, NodeCounter};
+#[cfg(feature = "tape")]
+use super::autograd::tape::{self, is_recording, TapeRef};
+use super::autograd::{is_anomaly_enabled, is_grad_enabled, nan_policy, NanPolicy};
+use super::float::{Float, Scalar};
+use super::ops::binary_ops::BinaryKind;
+use super::ops::{BinaryOps, CustomFn, CustomOp, Op, Ops, UnaryOps};
+use super::shared::{self, Lock, MappedMut, MaybeSync, Shared};
 use ndarray::ScalarOperand;
 use num_traits::{FromPrimitive, One, Zero};
-use ordered_float::NotNan;
 
-use std::cell::{RefCell, RefMut};
-use std::f64::consts::E;
-use std::iter::Sum;
+use std::iter::{zip, Sum};
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
-use std::rc::Rc;
-use std::{fmt, mem};
+use std::{fmt, mem, slice};
 
 #[macro_export]
 macro_rules! values {
@@ -32,50 +36,345 @@ macro_rules! val {
     ($x: expr) => {
         Value::from($x)
     };
+    ($x: expr; $float: ty $(, $mth:ident = $val:expr)*) => {{
+        #[allow(unused_mut)]
+        let mut value = Value::<$float>::new($x);
+        $( value.$mth($val); )*
+        value
+    }};
 }
 
 #[derive(Default)]
-pub struct Data {
-    pub value: NotNan<f64>,
-    grad: Option<Value>,
-    operation: Ops,
-    back_pass: bool,
+pub struct Data<F: Float = f64> {
+    pub value: F,
+    grad: Option<Value<F>>,
     requires_grad: bool,
+    retains_grad: bool,
+    extras: Option<Box<Extras<F>>>,
+    _counter: NodeCounter,
+}
+
+impl<F: Float> Data<F> {
+    fn extras(&self) -> Option<&Extras<F>> {
+        self.extras.as_deref()
+    }
+
+    fn extras_mut(&mut self) -> &mut Extras<F> {
+        self.extras.get_or_insert_with(Default::default)
+    }
+}
+
+// State that most nodes never use, boxed so that a plain node only pays for
+// one pointer.
+#[derive(Default)]
+struct Extras<F: Float> {
+    tangent: Option<F>,
+    creation: Option<Creation>,
+    hooks: Vec<Hook<F>>,
+}
+
+impl<F: Float> Extras<F> {
+    fn boxed(tangent: Option<F>, creation: Option<Creation>) -> Option<Box<Self>> {
+        if tangent.is_none() && creation.is_none() {
+            return None;
+        }
+
+        Some(Box::new(Extras {
+            tangent,
+            creation,
+            hooks: vec![],
+        }))
+    }
+}
+
+#[cfg(not(feature = "sync"))]
+pub(crate) type Hook<F> = Shared<dyn Fn(&Value<F>) -> Option<Value<F>>>;
+#[cfg(feature = "sync")]
+pub(crate) type Hook<F> = Shared<dyn Fn(&Value<F>) -> Option<Value<F>> + Send + Sync>;
+
+// Snapshot of the op and operand values a node was built from, only kept
+// while anomaly detection is enabled.
+struct Creation {
+    op: String,
+    operands: Vec<f64>,
+}
+
+impl Creation {
+    fn new<F: Float>(operation: &Ops<F>) -> Self {
+        Creation {
+            op: operation.name(),
+            operands: operation
+                .visible_operands()
+                .iter()
+                .map(|operand| operand.value().as_f64())
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for Creation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let operands: Vec<String> = self.operands.iter().map(f64::to_string).collect();
+        write!(f, "{}({})", self.op, operands.join(", "))
+    }
+}
+
+pub struct Value<F: Float = f64>(Node<F>);
+
+// A value is either a reference-counted graph node or, with the `tape`
+// feature, a handle into the tape of the current thread.
+enum Node<F: Float> {
+    Graph(Shared<GraphNode<F>>),
+    #[cfg(feature = "tape")]
+    Tape(TapeRef),
+}
+
+// The op of a graph node never changes once it is built, so it sits outside
+// the lock and can be read while the rest of the node is borrowed.
+struct GraphNode<F: Float> {
+    operation: Ops<F>,
+    data: Lock<Data<F>>,
+}
+
+impl<F: Float> GraphNode<F> {
+    fn shared(data: Data<F>, operation: Ops<F>) -> Shared<Self> {
+        Shared::new(GraphNode {
+            operation,
+            data: Lock::new(data),
+        })
+    }
 }
 
-pub struct Value(Rc<RefCell<Data>>);
+pub(crate) type NodeId = *const ();
+
+impl<F: Float> Value<F> {
+    pub fn new(value: F) -> Self {
+        if value.is_nan() && nan_policy() == NanPolicy::Panic {
+            panic!("Value cannot be NaN");
+        }
 
-impl Value {
-    pub fn new(value: f64) -> Self {
         let data = Data {
-            value: NotNan::new(value).expect("Value cannot be NaN"),
+            value,
             requires_grad: true,
             ..Default::default()
         };
 
-        Value(Rc::new(RefCell::new(data)))
+        Value(Node::Graph(GraphNode::shared(data, Ops::NoOp)))
     }
 
-    pub fn with_op<T: Op + Into<Ops>>(value: f64, operation: T) -> Self {
+    pub(crate) fn constant(value: F) -> Self {
+        #[cfg(feature = "tape")]
+        if is_recording() && is_grad_enabled() {
+            return Value::from_tape(tape::push_constant(value));
+        }
+
+        let mut constant = Value::new(value);
+        constant.requires_grad(false);
+        constant
+    }
+
+    pub(crate) fn unary(operation: UnaryOps<F>) -> Self {
+        let value = operation.kind().forward(operation.variables()[0].value());
+        Value::with_op(value, operation)
+    }
+
+    pub fn with_op<T: Op<F> + Into<Ops<F>>>(value: F, operation: T) -> Self {
+        let operation = operation.into();
+        profiler::record_op(|| operation.name());
+        let creation = Value::check_creation(value, &operation);
+        #[cfg(feature = "tape")]
+        if is_recording() && is_grad_enabled() {
+            return Value::from_tape(tape::push_op(value, &operation));
+        }
+        let tangent = Value::push_tangent(value, &operation);
+
+        if !is_grad_enabled() {
+            let Some(tangent) = tangent else {
+                return Value::constant(value);
+            };
+            let mut dual = Value::dual(value, tangent);
+            dual.requires_grad(false);
+            return dual;
+        }
+
+        let requires_grad = operation
+            .variables()
+            .iter()
+            .any(|operand| operand.should_compute_grad());
+        let counter = if operation.is_hidden() {
+            NodeCounter::hidden()
+        } else {
+            NodeCounter::default()
+        };
         let data = Data {
-            value: NotNan::new(value).expect("Value cannot be NaN"),
-            operation: operation.into(),
-            requires_grad: true,
-            ..Default::default()
+            value,
+            grad: None,
+            requires_grad,
+            retains_grad: false,
+            extras: Extras::boxed(tangent, creation),
+            _counter: counter,
         };
 
-        Value(Rc::new(RefCell::new(data)))
+        Value(Node::Graph(GraphNode::shared(data, operation)))
+    }
+
+    #[cfg(feature = "tape")]
+    pub(crate) fn from_tape(tape_ref: TapeRef) -> Self {
+        Value(Node::Tape(tape_ref))
+    }
+
+    #[cfg(feature = "tape")]
+    pub(crate) fn tape_ref(&self) -> Option<TapeRef> {
+        match self.0 {
+            Node::Graph(_) => None,
+            Node::Tape(tape_ref) => Some(tape_ref),
+        }
+    }
+
+    fn node(&self) -> &Shared<GraphNode<F>> {
+        match &self.0 {
+            Node::Graph(node) => node,
+            #[cfg(feature = "tape")]
+            Node::Tape(_) => panic!("Tape values only support reading their value and backward"),
+        }
+    }
+
+    fn node_mut(&mut self) -> Option<&mut Shared<GraphNode<F>>> {
+        match &mut self.0 {
+            Node::Graph(node) => Some(node),
+            #[cfg(feature = "tape")]
+            Node::Tape(_) => None,
+        }
+    }
+
+    fn data(&self) -> &Lock<Data<F>> {
+        &self.node().data
+    }
+
+    // Graph node behind a public method that tape values cannot support.
+    #[cfg_attr(not(feature = "tape"), allow(unused_variables))]
+    fn graph_data(&self, method: &str) -> &Lock<Data<F>> {
+        match &self.0 {
+            Node::Graph(node) => &node.data,
+            #[cfg(feature = "tape")]
+            Node::Tape(_) => panic!(
+                "Value::{method} is not supported on tape values, create the value outside of autograd::tape()"
+            ),
+        }
+    }
+
+    #[cfg_attr(not(feature = "tape"), allow(unused_variables))]
+    pub(crate) fn assert_graph(&self, context: &str) {
+        #[cfg(feature = "tape")]
+        assert!(
+            self.tape_ref().is_none(),
+            "{context} does not support tape values, call it outside of autograd::tape()"
+        );
+    }
+
+    // Forward-mode step: the tangent of a node is the sum of its operands'
+    // tangents scaled by the same partials the engine uses in reverse mode.
+    fn push_tangent(value: F, operation: &Ops<F>) -> Option<F> {
+        let tangents: Vec<Option<F>> = operation
+            .variables()
+            .iter()
+            .map(|operand| operand.tangent())
+            .collect();
+        if tangents.iter().all(Option::is_none) {
+            return None;
+        }
+
+        let partials = operation.partials(&Value::constant(value));
+        let tangent = zip(partials, tangents)
+            .filter_map(|(partial, tangent)| tangent.map(|tangent| partial * tangent))
+            .sum();
+
+        Some(tangent)
+    }
+
+    fn check_creation(value: F, operation: &Ops<F>) -> Option<Creation> {
+        if !is_anomaly_enabled() {
+            if value.is_nan() && nan_policy() == NanPolicy::Panic {
+                panic!(
+                    "Value cannot be NaN: {} produced NaN",
+                    Creation::new(operation)
+                );
+            }
+            return None;
+        }
+
+        let creation = Creation::new(operation);
+        if !value.is_finite() {
+            let mut message = format!("Anomaly detected: {creation} produced {value}");
+            for (idx, operand) in operation.visible_operands().iter().enumerate() {
+                message += &format!("\n  operand {idx}: {}", operand.describe());
+            }
+            panic!("{message}");
+        }
+
+        Some(creation)
+    }
+
+    pub fn custom<B>(value: F, operands: Vec<Value<F>>, backward: B) -> Self
+    where
+        B: Fn(&[Value<F>], &Value<F>, &Value<F>) -> Vec<Value<F>> + MaybeSync + 'static,
+    {
+        Value::with_custom_op(value, CustomFn { operands, backward })
+    }
+
+    /// Creates a leaf dual number whose tangent is carried forward through
+    /// every op it takes part in.
+    pub fn dual(value: F, tangent: F) -> Self {
+        let dual = Value::new(value);
+        dual.set_tangent(Some(tangent));
+        dual
+    }
+
+    pub fn with_custom_op<T: CustomOp<F> + 'static>(value: F, operation: T) -> Self {
+        let operation: Box<dyn CustomOp<F>> = Box::new(operation);
+        Value::with_op(value, operation)
+    }
+
+    pub fn register_hook<H>(&self, hook: H)
+    where
+        H: Fn(&Value<F>) -> Option<Value<F>> + MaybeSync + 'static,
+    {
+        self.graph_data("register_hook")
+            .borrow_mut()
+            .extras_mut()
+            .hooks
+            .push(Shared::new(hook));
     }
 
     pub fn requires_grad(&mut self, requires: bool) {
-        self.0.borrow_mut().requires_grad = requires;
+        self.graph_data("requires_grad").borrow_mut().requires_grad = requires;
     }
 
     pub fn should_compute_grad(&self) -> bool {
-        self.0.borrow().requires_grad
+        match &self.0 {
+            Node::Graph(node) => node.data.borrow().requires_grad,
+            #[cfg(feature = "tape")]
+            Node::Tape(tape_ref) => tape::requires_grad::<F>(*tape_ref),
+        }
+    }
+
+    pub fn retain_grad(&self) {
+        self.graph_data("retain_grad").borrow_mut().retains_grad = true;
+    }
+
+    pub fn retains_grad(&self) -> bool {
+        match &self.0 {
+            Node::Graph(node) => node.data.borrow().retains_grad,
+            #[cfg(feature = "tape")]
+            Node::Tape(_) => false,
+        }
     }
 
-    pub fn max(self, other: Value) -> Self {
+    pub fn detach(&self) -> Self {
+        Value::constant(self.value())
+    }
+
+    pub fn max(self, other: Value<F>) -> Self {
         if self.value() > other.value() {
             self
         } else {
@@ -83,155 +382,290 @@ impl Value {
         }
     }
 
-    pub fn value(&self) -> f64 {
-        self.0.borrow().value.into_inner()
+    pub fn value(&self) -> F {
+        match &self.0 {
+            Node::Graph(node) => node.data.borrow().value,
+            #[cfg(feature = "tape")]
+            Node::Tape(tape_ref) => tape::value(*tape_ref),
+        }
     }
 
-    pub fn value_mut(&self) -> RefMut<NotNan<f64>> {
-        RefMut::map(self.0.borrow_mut(), |data| &mut data.value)
+    pub fn value_mut(&self) -> MappedMut<F> {
+        shared::map_mut(self.graph_data("value_mut").borrow_mut(), |data| {
+            &mut data.value
+        })
     }
 
-    pub fn grad(&self) -> Option<Value> {
-        self.0.borrow().grad.clone()
+    pub fn grad(&self) -> Option<Value<F>> {
+        match &self.0 {
+            Node::Graph(node) => node.data.borrow().grad.clone(),
+            #[cfg(feature = "tape")]
+            Node::Tape(_) => None,
+        }
     }
 
-    pub fn grad_mut(&self) -> RefMut<Value> {
-        RefMut::map(self.0.borrow_mut(), |data| {
+    pub fn grad_mut(&self) -> MappedMut<Value<F>> {
+        shared::map_mut(self.graph_data("grad_mut").borrow_mut(), |data| {
             data.grad.get_or_insert(Value::zero())
         })
     }
 
     pub fn zero_grad(&self) {
-        let mut data = self.0.borrow_mut();
-        data.grad = None;
-        data.back_pass = false;
+        match &self.0 {
+            Node::Graph(node) => node.data.borrow_mut().grad = None,
+            #[cfg(feature = "tape")]
+            Node::Tape(_) => (),
+        }
     }
 
-    pub fn powf<T: Into<f64>>(&self, raw_exponent: T) -> Self {
-        let mut exponent = val!(raw_exponent, requires_grad = false);
-        exponent.requires_grad(false);
+    pub fn tangent(&self) -> Option<F> {
+        match &self.0 {
+            Node::Graph(node) => node
+                .data
+                .borrow()
+                .extras()
+                .and_then(|extras| extras.tangent),
+            #[cfg(feature = "tape")]
+            Node::Tape(_) => None,
+        }
+    }
 
-        let value = self.value().powf(exponent.value());
-        Value::with_op(value, BinaryOps::Pow(self.clone(), exponent))
+    pub fn set_tangent(&self, tangent: Option<F>) {
+        let mut data = self.graph_data("set_tangent").borrow_mut();
+        if tangent.is_some() || data.extras.is_some() {
+            data.extras_mut().tangent = tangent;
+        }
+    }
+
+    pub fn powf<T: Into<F>>(&self, exponent: T) -> Self {
+        self.pow(Value::constant(exponent.into()))
     }
 
     pub fn pow(&self, exponent: Self) -> Self {
-        let value = self.value().powf(exponent.value());
+        let value = BinaryKind::Pow.forward(self.value(), exponent.value());
         Value::with_op(value, BinaryOps::Pow(self.clone(), exponent))
     }
 
     pub fn sqrt(&self) -> Self {
-        self.powf(0.5)
+        self.powf(F::cast(0.5))
     }
 
     pub fn exp(&self) -> Self {
-        let value = E.powf(self.value());
-        Value::with_op(value, UnaryOps::Exp(self.clone()))
+        Value::unary(UnaryOps::Exp(self.clone()))
     }
 
     pub fn log(&self) -> Self {
-        let value = self.value().ln();
-        Value::with_op(value, UnaryOps::Log(self.clone()))
+        Value::unary(UnaryOps::Log(self.clone()))
+    }
+
+    pub fn log1p(&self) -> Self {
+        Value::unary(UnaryOps::Log1p(self.clone()))
+    }
+
+    pub fn expm1(&self) -> Self {
+        Value::unary(UnaryOps::Expm1(self.clone()))
+    }
+
+    pub fn sin(&self) -> Self {
+        Value::unary(UnaryOps::Sin(self.clone()))
+    }
+
+    pub fn cos(&self) -> Self {
+        Value::unary(UnaryOps::Cos(self.clone()))
+    }
+
+    pub fn tanh(&self) -> Self {
+        Value::unary(UnaryOps::Tanh(self.clone()))
+    }
+
+    pub fn sigmoid(&self) -> Self {
+        Value::unary(UnaryOps::Sigmoid(self.clone()))
+    }
+
+    pub fn abs(&self) -> Self {
+        Value::unary(UnaryOps::Abs(self.clone()))
+    }
+
+    pub fn softplus(&self) -> Self {
+        Value::unary(UnaryOps::Softplus(self.clone()))
+    }
+
+    pub fn erf(&self) -> Self {
+        Value::unary(UnaryOps::Erf(self.clone()))
+    }
+
+    pub fn clamp(&self, min: F, max: F) -> Self {
+        assert!(min <= max, "Cannot clamp when min is greater than max");
+
+        Value::unary(UnaryOps::Clamp(self.clone(), min, max))
     }
 
     pub fn backward(&self) {
-        let topo_order = self.topo_sort();
-        self.0.borrow_mut().grad = Some(Value::one());
+        self.backward_with(false);
+    }
 
-        for source in topo_order.iter().rev() {
-            let data = &source.0;
-            data.borrow_mut().back_pass = false;
-            data.borrow().operation.propagate(source);
+    pub fn backward_with(&self, create_graph: bool) {
+        #[cfg(feature = "tape")]
+        if let Node::Tape(tape_ref) = self.0 {
+            assert!(!create_graph, "Tape values cannot build a gradient graph");
+            return profiler::time_backward(|| tape::backward::<F>(tape_ref));
+        }
+
+        profiler::time_backward(|| {
+            let (topo_order, grads) = engine::execute(slice::from_ref(self), create_graph);
+            Value::apply_grads(topo_order, grads);
+        });
+    }
+
+    // Leaves and nodes asked to retain their gradient accumulate the
+    // gradients of a backward pass, while other nodes do not keep any.
+    pub(crate) fn apply_grads(topo_order: Vec<Value<F>>, grads: GradTable<F>) {
+        for node in topo_order {
+            let keeps_grad = node.is_leaf() || node.retains_grad();
+            match grads.get(&node) {
+                Some(grad) if keeps_grad => node.accumulate_grad(grad),
+                _ if keeps_grad => (),
+                _ => node.zero_grad(),
+            }
         }
     }
 
-    fn topo_sort(&self) -> Vec<Value> {
-        let mut order = vec![];
+    pub fn is_leaf(&self) -> bool {
+        match &self.0 {
+            Node::Graph(node) => matches!(node.operation, Ops::NoOp),
+            #[cfg(feature = "tape")]
+            Node::Tape(tape_ref) => tape::is_leaf::<F>(*tape_ref),
+        }
+    }
 
-        let mut data = self.0.borrow_mut();
-        data.back_pass = true;
-        data.grad = Some(Value::zero());
+    fn is_unshared_leaf(&self) -> bool {
+        Shared::strong_count(self.node()) == 1 && self.is_leaf()
+    }
+
+    pub(crate) fn accumulate_grad(&self, grad: Grad<F>) {
+        let prev_grad = self.data().borrow_mut().grad.take();
 
-        let operation = &data.operation;
-        for operand in operation.variables() {
-            if !operand.0.borrow().back_pass {
-                order.append(&mut operand.topo_sort());
+        let new_grad = match (prev_grad, grad) {
+            (None, grad) => grad.into_value(),
+            (Some(prev_grad), Grad::Raw(grad)) if prev_grad.is_unshared_leaf() => {
+                *prev_grad.value_mut() += grad;
+                prev_grad
             }
+            (Some(prev_grad), Grad::Raw(grad)) => Value::new(prev_grad.value() + grad),
+            (Some(prev_grad), Grad::Graph(grad)) => prev_grad + grad,
+        };
+
+        self.data().borrow_mut().grad = Some(new_grad);
+    }
+
+    pub(crate) fn id(&self) -> NodeId {
+        Shared::as_ptr(self.node()) as *const () as NodeId
+    }
+
+    pub(crate) fn operation(&self) -> &Ops<F> {
+        &self.node().operation
+    }
+
+    pub(crate) fn hooks(&self) -> Vec<Hook<F>> {
+        self.data()
+            .borrow()
+            .extras()
+            .map_or_else(Vec::new, |extras| extras.hooks.clone())
+    }
+
+    pub(crate) fn describe(&self) -> String {
+        #[cfg(feature = "tape")]
+        if self.tape_ref().is_some() {
+            return format!("Tape({})", self.value());
         }
 
-        order.push(self.clone());
-        order
+        let node = self.node();
+        let data = node.data.borrow();
+        let creation = data.extras().and_then(|extras| extras.creation.as_ref());
+        match (creation, &node.operation) {
+            (Some(creation), _) => creation.to_string(),
+            (None, Ops::NoOp) => format!("Leaf({})", data.value),
+            (None, operation) => Creation::new(operation).to_string(),
+        }
     }
 }
 
-impl Drop for Value {
+impl<F: Float> Drop for Value<F> {
     fn drop(&mut self) {
-        if Rc::strong_count(&self.0) > 1 {
+        let Some(node) = self.node_mut() else {
             return;
-        }
+        };
 
-        let refrences = |data: &Rc<RefCell<Data>>| {
-            let grad_op = mem::take(&mut data.borrow_mut().grad);
+        // Unique nodes hand over their gradient and operands, whose own drops
+        // then find nothing left to walk instead of recursing.
+        let refrences = |node: &mut Shared<GraphNode<F>>| {
+            let Some(node) = Shared::get_mut(node) else {
+                return vec![];
+            };
+
+            let grad_op = node.data.get_mut().grad.take();
             let mut refrences = if let Some(grad) = grad_op {
                 vec![grad]
             } else {
                 vec![]
             };
 
-            let ops = mem::take(&mut data.borrow_mut().operation);
+            let ops = mem::take(&mut node.operation);
             let vars = ops.into_inner();
             refrences.extend(vars);
             refrences
         };
-        let mut stack: Vec<Value> = refrences(&self.0);
+        let mut stack: Vec<Value<F>> = refrences(node);
 
         while let Some(mut curr) = stack.pop() {
-            let data = mem::take(&mut curr.0);
-
-            if Rc::strong_count(&data) == 1 {
-                stack.extend(refrences(&data))
+            if let Some(node) = curr.node_mut() {
+                stack.extend(refrences(node));
             }
         }
     }
 }
 
-impl Default for Value {
+impl<F: Float> Default for Value<F> {
     fn default() -> Self {
         Value::zero()
     }
 }
 
-impl Clone for Value {
+impl<F: Float> Clone for Value<F> {
     fn clone(&self) -> Self {
-        Value(self.0.clone())
+        match &self.0 {
+            Node::Graph(node) => Value(Node::Graph(node.clone())),
+            #[cfg(feature = "tape")]
+            Node::Tape(tape_ref) => Value(Node::Tape(*tape_ref)),
+        }
     }
 }
 
-impl PartialEq for Value {
+impl<F: Float> PartialEq for Value<F> {
     fn eq(&self, other: &Self) -> bool {
         self.value() == other.value()
     }
 }
 
-impl FromPrimitive for Value {
+impl<F: Float> FromPrimitive for Value<F> {
     fn from_f64(n: f64) -> Option<Self> {
-        Some(val!(n))
+        Some(Value::new(F::cast(n)))
     }
 
     fn from_i64(x: i64) -> Option<Self> {
-        Some(val!(x as f64))
+        Some(Value::new(F::cast(x as f64)))
     }
 
     fn from_u64(x: u64) -> Option<Self> {
-        Some(val!(x as f64))
+        Some(Value::new(F::cast(x as f64)))
     }
 }
 
-impl ScalarOperand for Value {}
+impl<F: Float> ScalarOperand for Value<F> {}
 
-impl Zero for Value {
+impl<F: Float> Zero for Value<F> {
     fn zero() -> Self {
-        val!(0.0)
+        Value::new(F::zero())
     }
 
     fn set_zero(&mut self) {
@@ -243,9 +677,9 @@ impl Zero for Value {
     }
 }
 
-impl One for Value {
+impl<F: Float> One for Value<F> {
     fn one() -> Self {
-        val!(1.0)
+        Value::new(F::one())
     }
 
     fn set_one(&mut self) {
@@ -266,35 +700,36 @@ where
     }
 }
 
-impl Sum<Self> for Value {
+impl<F: Float> Sum<Self> for Value<F> {
     fn sum<I>(iter: I) -> Self
     where
         I: Iterator<Item = Self>,
     {
-        iter.fold(Value::new(0.0), |sum, other| sum + other)
+        iter.reduce(|sum, other| sum + other)
+            .unwrap_or_else(Value::zero)
     }
 }
 
-impl Neg for Value {
-    type Output = Value;
+impl<F: Float> Neg for Value<F> {
+    type Output = Value<F>;
 
     fn neg(self) -> Self::Output {
-        Value::zero() - self
+        Value::constant(F::zero()) - self
     }
 }
 
-impl Neg for &Value {
-    type Output = Value;
+impl<F: Float> Neg for &Value<F> {
+    type Output = Value<F>;
 
     fn neg(self) -> Self::Output {
-        &Value::zero() - self
+        &Value::constant(F::zero()) - self
     }
 }
 
 macro_rules! impl_binary_ops {
     ($trait: ident, $mth: ident, $operator: tt, $op_varient: tt) => {
-        impl $trait<Value> for Value {
-            type Output = Value;
+        impl<F: Float> $trait<Value<F>> for Value<F> {
+            type Output = Value<F>;
 
             fn $mth(self, rhs: Self) -> Self::Output {
                 let result = self.value() $operator rhs.value();
@@ -304,8 +739,8 @@ macro_rules! impl_binary_ops {
             }
         }
 
-        impl<'a> $trait<&'a Value> for &'a Value {
-            type Output = Value;
+        impl<'a, F: Float> $trait<&'a Value<F>> for &'a Value<F> {
+            type Output = Value<F>;
 
             fn $mth(self, rhs: Self) -> Self::Output {
                 let result = self.value() $operator rhs.value();
@@ -315,12 +750,11 @@ macro_rules! impl_binary_ops {
             }
         }
 
-        impl<T> $trait<T> for Value where T: Into<f64> {
-            type Output = Value;
+        impl<F: Float, T: Scalar<F>> $trait<T> for Value<F> {
+            type Output = Value<F>;
 
             fn $mth(self, rhs: T) -> Self::Output {
-                let mut rhs_val = val!(rhs);
-                rhs_val.requires_grad(false);
+                let rhs_val = Value::constant(rhs.into_float());
 
                 let value = self.value() $operator rhs_val.value();
                 let operation = BinaryOps::$op_varient(self, rhs_val);
@@ -329,12 +763,11 @@ macro_rules! impl_binary_ops {
             }
         }
 
-        impl<'a, T> $trait<&'a T> for &'a Value where T: Into<f64> + Copy {
-            type Output = Value;
+        impl<'a, F: Float, T: Scalar<F>> $trait<&'a T> for &'a Value<F> {
+            type Output = Value<F>;
 
             fn $mth(self, rhs: &'a T) -> Self::Output {
-                let mut rhs_val = val!(*rhs);
-                rhs_val.requires_grad(false);
+                let rhs_val = Value::constant(rhs.into_float());
 
                 let value = self.value() $operator rhs_val.value();
                 let operation = BinaryOps::$op_varient(self.clone(), rhs_val);
@@ -352,14 +785,14 @@ impl_binary_ops!(Div, div, /, Div);
 
 macro_rules! impl_binary_assign_ops {
     ($trait: ident, $mth: ident, $operator: tt) => {
-        impl $trait for Value {
+        impl<F: Float> $trait for Value<F> {
             fn $mth(&mut self, rhs: Self) {
                 *self = self.clone() $operator rhs;
             }
         }
 
-        impl $trait<&Value> for Value {
-            fn $mth(&mut self, rhs: &Value) {
+        impl<F: Float> $trait<&Value<F>> for Value<F> {
+            fn $mth(&mut self, rhs: &Value<F>) {
                 *self = self.clone() $operator rhs.clone();
             }
         }
@@ -371,13 +804,8 @@ impl_binary_assign_ops!(SubAssign, sub_assign, -);
 impl_binary_assign_ops!(MulAssign, mul_assign, *);
 impl_binary_assign_ops!(DivAssign, div_assign, /);
 
-impl fmt::Debug for Value {
+impl<F: Float> fmt::Debug for Value<F> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "Value(data: {}, grad: {:?})",
-            self.value(),
-            self.0.borrow().grad,
-        )
+        write!(f, "Value(data: {}, grad: {:?})", self.value(), self.grad())
     }
 }
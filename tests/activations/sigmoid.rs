@@ -1,4 +1,5 @@
 extern crate micrograd_rs;
+use approx::assert_abs_diff_eq;
 use micrograd_rs::activations as Activation;
 use micrograd_rs::prelude::*;
 use micrograd_rs::Layer;
@@ -22,5 +23,19 @@ fn valid_sigmoid_activation() {
         0.9868746816628972,
     ];
 
-    assert_eq!(outputs, actuals);
+    for (output, actual) in outputs.into_iter().zip(actuals) {
+        assert_abs_diff_eq!(output, actual, epsilon = 1e-15);
+    }
+}
+
+#[test]
+fn valid_sigmoid_activation_for_large_inputs() {
+    let inputs = tensor![-1000.0, -40.0, 40.0, 1000.0];
+    let outputs = Activation::Sigmoid.forward(&inputs).mapv(|v| v.value());
+
+    let actuals = [0.0, 4.248354255291589e-18, 1.0, 1.0];
+
+    for (output, actual) in outputs.into_iter().zip(actuals) {
+        assert_abs_diff_eq!(output, actual, epsilon = 1e-30);
+    }
 }
@@ -18,3 +18,15 @@ fn valid_tanh_activation() {
         assert_abs_diff_eq!(output, actual, epsilon = 1e-6);
     }
 }
+
+#[test]
+fn valid_tanh_activation_for_large_inputs() {
+    let inputs = tensor![-1000.0, 400.0, 1000.0];
+    let outputs = Activation::Tanh.forward(&inputs).mapv(|v| v.value());
+
+    let actuals = [-1.0, 1.0, 1.0];
+
+    for (output, actual) in outputs.into_iter().zip(actuals) {
+        assert_abs_diff_eq!(output, actual, epsilon = 1e-12);
+    }
+}
@@ -0,0 +1,89 @@
+extern crate micrograd_rs;
+use approx::assert_abs_diff_eq;
+use micrograd_rs::criterions::{Criterion, Reduction, MSE};
+use micrograd_rs::optim::{Optimizer, SGD};
+use micrograd_rs::prelude::*;
+use micrograd_rs::Sequential;
+
+use super::{batches, build_model};
+
+fn batch_loss(model: &Sequential<Ix2>, (x, y): (Array2<f64>, Array2<f64>)) -> Value {
+    let (x, y) = (Tensor::from_f64(&x, true), Tensor::from_f64(&y, false));
+    MSE::loss(Reduction::Sum, &model.forward(&x), &y)
+}
+
+#[test]
+fn valid_grads_accumulate_across_backward_calls() {
+    let x = Value::from(3.0);
+
+    (&x * &2.0).backward();
+    x.powf(2.0).backward();
+    assert_eq!(x.grad().unwrap().value(), 8.0);
+
+    x.zero_grad();
+    x.powf(2.0).backward();
+    assert_eq!(x.grad().unwrap().value(), 6.0);
+}
+
+#[test]
+fn valid_accumulated_batches_match_combined_loss() {
+    let model = build_model();
+    let params = model.parameters();
+
+    for batch in batches() {
+        batch_loss(&model, batch).backward();
+    }
+    let accumulated = params.grads();
+
+    params.zero_grad();
+    let combined = batches()
+        .into_iter()
+        .map(|batch| batch_loss(&model, batch))
+        .sum::<Value>();
+    combined.backward();
+
+    for (accumulated, combined) in accumulated.into_iter().zip(params.grads()) {
+        assert_abs_diff_eq!(accumulated, combined, epsilon = 1e-12);
+    }
+}
+
+#[test]
+fn valid_optimizer_step_after_accumulated_batches() {
+    let accumulated_model = build_model();
+    let combined_model = build_model();
+
+    let accumulated_params = accumulated_model.parameters().into_raw_vec();
+    let combined_params = combined_model.parameters().into_raw_vec();
+    for (accumulated, combined) in accumulated_params.iter().zip(&combined_params) {
+        *combined.value_mut() = accumulated.value();
+    }
+
+    let mut accumulated_optim = SGD {
+        params: accumulated_params.clone(),
+        lr: val!(0.1),
+        ..Default::default()
+    };
+    let mut combined_optim = SGD {
+        params: combined_params.clone(),
+        lr: val!(0.1),
+        ..Default::default()
+    };
+
+    accumulated_optim.zero_grad();
+    for batch in batches() {
+        batch_loss(&accumulated_model, batch).backward();
+    }
+    accumulated_optim.step();
+
+    combined_optim.zero_grad();
+    let combined = batches()
+        .into_iter()
+        .map(|batch| batch_loss(&combined_model, batch))
+        .sum::<Value>();
+    combined.backward();
+    combined_optim.step();
+
+    for (accumulated, combined) in accumulated_params.iter().zip(&combined_params) {
+        assert_abs_diff_eq!(accumulated.value(), combined.value(), epsilon = 1e-12);
+    }
+}
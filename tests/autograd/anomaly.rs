@@ -0,0 +1,101 @@
+extern crate micrograd_rs;
+use micrograd_rs::autograd::{
+    detect_anomaly, is_anomaly_enabled, nan_policy, set_nan_policy, NanPolicy,
+};
+use micrograd_rs::prelude::*;
+use std::panic;
+
+#[test]
+#[should_panic(expected = "Value cannot be NaN: Div(0, 0) produced NaN")]
+fn invalid_nan_panics_by_default() {
+    let zero = Value::from(0.0);
+    let _ = &zero / &zero;
+}
+
+#[test]
+#[should_panic(expected = "Gradient cannot be NaN: backward of Pow(0, 0.5) produced NaN")]
+fn invalid_nan_grad_panics_by_default() {
+    let x = Value::from(0.0);
+    let y = x.sqrt() * 0.0;
+    y.backward();
+}
+
+#[test]
+fn valid_propagate_policy_lets_training_skip_nan_steps() {
+    let policy = set_nan_policy(NanPolicy::Propagate);
+    assert_eq!(nan_policy(), NanPolicy::Propagate);
+
+    let w = Value::from(0.0);
+    let mut steps_taken = 0;
+    for target in [0.0, 2.0] {
+        let loss = (&w - &target).powf(2.0) / (&w - &0.0);
+        if loss.value().is_nan() {
+            continue;
+        }
+
+        loss.backward();
+        steps_taken += 1;
+    }
+
+    assert_eq!(steps_taken, 1);
+    assert!(w.grad().unwrap().value().is_infinite());
+
+    drop(policy);
+    assert_eq!(nan_policy(), NanPolicy::Panic);
+}
+
+#[test]
+fn valid_nan_policy_restored_after_panic() {
+    let result = panic::catch_unwind(|| {
+        let _policy = set_nan_policy(NanPolicy::Propagate);
+        panic!("Training step failed");
+    });
+
+    assert!(result.is_err());
+    assert_eq!(nan_policy(), NanPolicy::Panic);
+}
+
+#[test]
+#[should_panic(expected = "Anomaly detected: Log(0) produced -inf")]
+fn invalid_anomaly_mode_reports_forward_op() {
+    let _guard = detect_anomaly();
+    let x = Value::from(0.0);
+    let _ = x.log();
+}
+
+#[test]
+#[should_panic(expected = "operand 0: Sub(1, 1)")]
+fn invalid_anomaly_mode_reports_operand_origin() {
+    let _guard = detect_anomaly();
+    let x = Value::from(1.0);
+    let _ = (&x - &1.0).log();
+}
+
+#[test]
+#[should_panic(
+    expected = "Anomaly detected: backward of Pow(0, 0.5) produced inf for operand 0\n  node path: Add -> Mul -> Pow"
+)]
+fn invalid_anomaly_mode_reports_backward_path() {
+    let _guard = detect_anomaly();
+    let x = Value::from(0.0);
+    let y = x.sqrt() * 2.0 + 1.0;
+    y.backward();
+}
+
+#[test]
+fn valid_anomaly_mode_passes_finite_graphs() {
+    assert!(!is_anomaly_enabled());
+
+    let x = Value::from(4.0);
+    {
+        let _guard = detect_anomaly();
+        assert!(is_anomaly_enabled());
+
+        let y = x.sqrt() * x.log();
+        y.backward();
+    }
+
+    assert!(!is_anomaly_enabled());
+    let expected = 0.25 * 4f64.ln() + 2.0 / 4.0;
+    assert!((x.grad().unwrap().value() - expected).abs() < 1e-12);
+}
@@ -0,0 +1,129 @@
+extern crate micrograd_rs;
+use approx::assert_abs_diff_eq;
+use micrograd_rs::autograd::capture;
+use micrograd_rs::criterions::{Criterion, Reduction, MSE};
+use micrograd_rs::optim::{Optimizer, SGD};
+use micrograd_rs::prelude::*;
+use micrograd_rs::Sequential;
+
+use super::{batches, build_model};
+
+#[test]
+fn valid_replay_matches_graph() {
+    let model = build_model();
+    let inputs = Tensor::from_shape_simple_fn((2, 3), || val!(0.0));
+    let targets = Tensor::from_shape_simple_fn((2, 2), || val!(0.0, requires_grad = false));
+
+    let loss = MSE::loss(Reduction::Mean, &model.forward(&inputs), &targets);
+    let placeholders = inputs.iter().chain(&targets).cloned().collect::<Vec<_>>();
+    let mut program = capture(&loss, &placeholders);
+    model.parameters().zero_grad();
+
+    for (x, y) in batches() {
+        let loss = MSE::loss(
+            Reduction::Mean,
+            &model.forward(&x.mapv(Value::new)),
+            &y.mapv(|y| val!(y, requires_grad = false)),
+        );
+        loss.backward();
+        let expected = model.parameters().grads();
+        model.parameters().zero_grad();
+
+        let values = x.iter().chain(&y).copied().collect::<Vec<f64>>();
+        assert_abs_diff_eq!(program.forward(&values), loss.value(), epsilon = 1e-12);
+        program.backward();
+
+        for (actual, expected) in model.parameters().grads().iter().zip(expected) {
+            assert_abs_diff_eq!(*actual, expected, epsilon = 1e-12);
+        }
+        model.parameters().zero_grad();
+    }
+}
+
+#[test]
+fn valid_replay_training_loop() {
+    let graph_model = build_model();
+    let program_model = build_model();
+    for (graph, program) in graph_model
+        .parameters()
+        .iter()
+        .zip(&program_model.parameters())
+    {
+        *program.value_mut() = graph.value();
+    }
+
+    let new_optimizer = |model: &Sequential<Ix2>| SGD {
+        params: model.parameters().to_vec(),
+        lr: val!(0.1),
+        momentum: 0.3,
+        ..Default::default()
+    };
+    let mut graph_optimizer = new_optimizer(&graph_model);
+    let mut program_optimizer = new_optimizer(&program_model);
+
+    let inputs = Tensor::from_shape_simple_fn((2, 3), || val!(0.0));
+    let targets = Tensor::from_shape_simple_fn((2, 2), || val!(0.0, requires_grad = false));
+    let loss = MSE::loss(Reduction::Sum, &program_model.forward(&inputs), &targets);
+    let placeholders = inputs.iter().chain(&targets).cloned().collect::<Vec<_>>();
+    let mut program = capture(&loss, &placeholders);
+
+    for (x, y) in batches().into_iter().cycle().take(9) {
+        let loss = MSE::loss(
+            Reduction::Sum,
+            &graph_model.forward(&x.mapv(Value::new)),
+            &y.mapv(|y| val!(y, requires_grad = false)),
+        );
+        graph_optimizer.zero_grad();
+        loss.backward();
+        graph_optimizer.step();
+
+        let values = x.iter().chain(&y).copied().collect::<Vec<f64>>();
+        let program_loss = program.forward(&values);
+        program_optimizer.zero_grad();
+        program.backward();
+        program_optimizer.step();
+
+        assert_abs_diff_eq!(program_loss, loss.value(), epsilon = 1e-9);
+    }
+
+    for (graph, program) in graph_model
+        .parameters()
+        .iter()
+        .zip(&program_model.parameters())
+    {
+        assert_abs_diff_eq!(graph.value(), program.value(), epsilon = 1e-9);
+    }
+}
+
+#[test]
+fn valid_replay_input_grads() {
+    let x = val!(0.0);
+    let y = val!(0.0);
+    let w = val!(3.0);
+
+    let z = (&x * &w).sin() + &y.powf(2.0).exp() / &w;
+    let mut program = capture(&z, &[x.clone(), y.clone()]);
+    assert_eq!(program.parameters(), vec![w.clone()]);
+
+    let value = program.forward(&[0.5, -1.0]);
+    program.backward();
+
+    assert_abs_diff_eq!(value, 1.5f64.sin() + 1.0f64.exp() / 3.0, epsilon = 1e-12);
+    let input_grads = program.input_grads();
+    assert_abs_diff_eq!(input_grads[0], 3.0 * 1.5f64.cos(), epsilon = 1e-12);
+    assert_abs_diff_eq!(input_grads[1], -2.0 * 1.0f64.exp() / 3.0, epsilon = 1e-12);
+    assert_abs_diff_eq!(
+        w.grad().unwrap().value(),
+        0.5 * 1.5f64.cos() - 1.0f64.exp() / 9.0,
+        epsilon = 1e-12
+    );
+}
+
+#[test]
+#[should_panic(expected = "Custom op")]
+fn invalid_capture_of_custom_op() {
+    let x = val!(2.0);
+    let y = Value::custom(4.0, vec![x.clone()], |_, _, grad| vec![grad * &4.0]);
+
+    capture(&y, &[x]);
+}
@@ -0,0 +1,101 @@
+extern crate micrograd_rs;
+use approx::assert_abs_diff_eq;
+use micrograd_rs::autograd;
+use micrograd_rs::prelude::*;
+use micrograd_rs::CustomOp;
+use std::slice;
+
+fn log_sum_exp(logits: &[Value]) -> Value {
+    let max = logits.iter().map(Value::value).fold(f64::MIN, f64::max);
+    let exp_sum = logits.iter().map(|x| (x.value() - max).exp()).sum::<f64>();
+    let value = max + exp_sum.ln();
+
+    Value::custom(value, logits.to_vec(), |operands, output, grad| {
+        operands
+            .iter()
+            .map(|x| grad * &(x - output).exp())
+            .collect()
+    })
+}
+
+struct Square(Value);
+
+impl CustomOp for Square {
+    fn operands(&self) -> Vec<&Value> {
+        vec![&self.0]
+    }
+
+    fn into_operands(self: Box<Self>) -> Vec<Value> {
+        vec![self.0]
+    }
+
+    fn backward(&self, _output: &Value, grad: &Value) -> Vec<Value> {
+        vec![grad * &(&self.0 * &2.0)]
+    }
+
+    fn name(&self) -> String {
+        String::from("Square")
+    }
+}
+
+#[test]
+fn valid_custom_op_backward() {
+    let logits = values![1000.0, 999.0, 998.5];
+    let lse = log_sum_exp(&logits);
+    lse.backward();
+
+    let exps = [1.0, (-1.0_f64).exp(), (-1.5_f64).exp()];
+    let total = exps.iter().sum::<f64>();
+
+    assert_abs_diff_eq!(lse.value(), 1000.0 + total.ln(), epsilon = 1e-9);
+    for (logit, exp) in logits.iter().zip(exps) {
+        assert_abs_diff_eq!(logit.grad().unwrap().value(), exp / total, epsilon = 1e-12);
+    }
+}
+
+#[test]
+fn valid_custom_op_composes_with_builtin_ops() {
+    let x = Value::from(3.0);
+    let y = Value::with_custom_op(9.0, Square(x.clone())) * 2.0 + x.clone();
+    y.backward();
+
+    assert_eq!(y.value(), 21.0);
+    assert_eq!(x.grad().unwrap().value(), 13.0);
+}
+
+#[test]
+fn valid_custom_op_higher_order_grads() {
+    let x = Value::from(3.0);
+    let y = Value::with_custom_op(9.0, Square(x.clone()));
+
+    let first = autograd::grad(&[y], slice::from_ref(&x), true).remove(0);
+    let second = autograd::grad(slice::from_ref(&first), slice::from_ref(&x), false).remove(0);
+
+    assert_eq!(first.value(), 6.0);
+    assert_eq!(second.value(), 2.0);
+}
+
+#[test]
+fn valid_custom_op_skips_constant_operands() {
+    let x = Value::from(2.0);
+    let c = val!(5.0, requires_grad = false);
+    let y = Value::custom(10.0, vec![x.clone(), c.clone()], |operands, _, grad| {
+        vec![grad * &operands[1], grad * &operands[0]]
+    });
+    y.backward();
+
+    assert_eq!(x.grad().unwrap().value(), 5.0);
+    assert!(c.grad().is_none());
+}
+
+#[test]
+fn valid_custom_op_drop() {
+    let mut a = Value::from(0.01);
+
+    for _ in 0..500_000 {
+        let value = a.value();
+        a = Value::custom(value, vec![a], |_, _, grad| vec![grad.clone()]);
+    }
+
+    assert_eq!(drop(a), ());
+}
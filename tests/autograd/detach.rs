@@ -0,0 +1,76 @@
+extern crate micrograd_rs;
+use micrograd_rs::prelude::*;
+
+#[test]
+fn valid_detach_cuts_graph() {
+    let x = Value::from(3.0);
+    let y = &x * &x;
+    let detached = y.detach();
+
+    let z = &detached * &x;
+    z.backward();
+
+    assert_eq!(detached.value(), 9.0);
+    assert!(detached.is_leaf());
+    assert!(!detached.should_compute_grad());
+    assert!(detached.grad().is_none());
+    assert_eq!(x.grad().unwrap().value(), 9.0);
+}
+
+#[test]
+fn valid_intermediate_grads_are_freed() {
+    let x = Value::from(2.0);
+    let hidden = &x * &3.0;
+    let y = hidden.exp();
+    y.backward();
+
+    assert!(hidden.grad().is_none());
+    assert!(y.grad().is_none());
+    assert_eq!(x.grad().unwrap().value(), 3.0 * y.value());
+}
+
+#[test]
+fn valid_retain_grad_keeps_intermediate_grad() {
+    let x = Value::from(2.0);
+    let hidden = &x * &3.0;
+    hidden.retain_grad();
+    let y = hidden.powf(2.0);
+    y.backward();
+
+    assert!(hidden.retains_grad());
+    assert_eq!(hidden.grad().unwrap().value(), 12.0);
+    assert_eq!(x.grad().unwrap().value(), 36.0);
+}
+
+#[test]
+fn valid_retained_grad_accumulates_like_leaves() {
+    let x = Value::from(2.0);
+    let hidden = &x * &3.0;
+    hidden.retain_grad();
+
+    hidden.powf(2.0).backward();
+    hidden.powf(2.0).backward();
+    assert_eq!(hidden.grad().unwrap().value(), 24.0);
+    assert_eq!(x.grad().unwrap().value(), 72.0);
+
+    let other = &x + &hidden;
+    (&other * &x).backward();
+    assert_eq!(hidden.grad().unwrap().value(), 26.0);
+}
+
+#[test]
+fn valid_tensor_detach_and_retain_grad() {
+    let x = tensor![1.0, 2.0, 3.0];
+    let hidden = x.mapv(|value| &value * &2.0);
+    hidden.retain_grad();
+
+    let detached = hidden.detach();
+    let loss: Value = hidden.iter().chain(detached.iter()).map(|h| h * h).sum();
+    loss.backward();
+
+    for ((x, h), d) in x.iter().zip(hidden.iter()).zip(detached.iter()) {
+        assert_eq!(h.grad().unwrap().value(), 2.0 * h.value());
+        assert_eq!(x.grad().unwrap().value(), 4.0 * h.value());
+        assert!(d.grad().is_none());
+    }
+}
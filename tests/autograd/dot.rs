@@ -0,0 +1,85 @@
+extern crate micrograd_rs;
+use micrograd_rs::autograd::DotOptions;
+use micrograd_rs::prelude::*;
+use std::fs;
+
+#[test]
+fn valid_dot_lists_nodes_and_edges() {
+    let x = Value::from(2.0);
+    let y = Value::from(3.0);
+    let z = (&x * &y).exp();
+
+    let dot = z.to_dot();
+
+    assert!(dot.starts_with("digraph {"));
+    assert!(dot.contains("n0 [label=\"Exp\\nvalue: "));
+    assert!(dot.contains("n1 [label=\"Mul\\nvalue: 6\\ngrad: None\", shape=box];"));
+    assert!(dot.contains("n2 [label=\"Leaf\\nvalue: 2\\ngrad: None\", shape=ellipse];"));
+    assert!(dot.contains("n1 -> n0;"));
+    assert!(dot.contains("n2 -> n1;"));
+    assert!(dot.contains("n3 -> n1;"));
+    assert_eq!(dot.matches(" -> ").count(), 3);
+}
+
+#[test]
+fn valid_dot_shows_grads_after_backward() {
+    let x = Value::from(2.0);
+    let y = &x * &x;
+    y.backward();
+
+    let dot = y.to_dot();
+
+    assert!(dot.contains("n1 [label=\"Leaf\\nvalue: 2\\ngrad: 4\", shape=ellipse];"));
+    assert_eq!(dot.matches("label=").count(), 2);
+    assert_eq!(dot.matches("n1 -> n0;").count(), 2);
+
+    let options = DotOptions {
+        show_grad: false,
+        ..Default::default()
+    };
+    assert!(!y.to_dot_with(&options).contains("grad"));
+}
+
+#[test]
+fn valid_dot_collapses_by_depth() {
+    let x = Value::from(1.0);
+    let mut y = x.clone();
+    for _ in 0..10 {
+        y = &y + &1.0;
+    }
+
+    let options = DotOptions {
+        max_depth: Some(2),
+        ..Default::default()
+    };
+    let dot = y.to_dot_with(&options);
+
+    assert_eq!(dot.matches("shape=box").count(), 3);
+    assert_eq!(dot.matches("label=\"...\"").count(), 1);
+    assert!(dot.contains("n3_collapsed -> n3 [style=dashed];"));
+}
+
+#[test]
+fn valid_dot_writes_file() {
+    let x = Value::from(2.0);
+    let y = x.sigmoid();
+    let path = std::env::temp_dir().join("micrograd_valid_dot_writes_file.dot");
+
+    y.write_dot(&path, &DotOptions::default()).unwrap();
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), y.to_dot());
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn valid_dot_hides_matmul_product() {
+    let a = tensor![[1.0, 2.0], [3.0, 4.0]];
+    let b = tensor![[5.0], [6.0]];
+
+    let dot = a.dot(&b)[[1, 0]].to_dot();
+
+    assert!(dot.contains("n0 [label=\"MatMulElement\\nvalue: 39\\ngrad: None\", shape=box];"));
+    assert!(!dot.contains("\"MatMul\\n"));
+    assert_eq!(dot.matches("shape=ellipse").count(), 4);
+    assert_eq!(dot.matches(" -> n0;").count(), 4);
+}
@@ -0,0 +1,89 @@
+extern crate micrograd_rs;
+use approx::assert_abs_diff_eq;
+use micrograd_rs::activations as Activation;
+use micrograd_rs::autograd::{self, no_grad};
+use micrograd_rs::prelude::*;
+use micrograd_rs::{Layer, Linear, Sequential};
+
+#[test]
+fn valid_dual_tangent_of_scalar_function() {
+    let x = Value::dual(2.0, 1.0);
+    let y = x.powf(3.0) + x.sin() - (x.clone() / 4.0).exp();
+
+    let expected = 3.0 * 4.0 + 2.0_f64.cos() - 0.25 * 0.5_f64.exp();
+    assert_abs_diff_eq!(y.tangent().unwrap(), expected, epsilon = 1e-12);
+}
+
+#[test]
+fn valid_tangent_is_none_without_dual_operands() {
+    let x = Value::from(2.0);
+    let y = x.tanh() * 3.0;
+
+    assert!(y.tangent().is_none());
+}
+
+#[test]
+fn valid_jvp_through_layers_matches_reverse_mode() {
+    let model = sequential!(
+        Ix1,
+        [
+            Linear::new("fc1", 3, 4),
+            Activation::Tanh,
+            Linear::new("fc2", 4, 2)
+        ]
+    );
+
+    let input = tensor![0.5, -1.0, 2.0];
+    let direction = array![1.0, 0.5, -2.0];
+
+    let (outputs, jvp) = autograd::jvp(|x| model.forward(x), &input, &direction);
+
+    let inputs = input.to_vec();
+    let reverse_outputs = model.forward(&input);
+    for ((dual_output, output), tangent) in outputs.iter().zip(reverse_outputs).zip(jvp) {
+        assert_abs_diff_eq!(dual_output.value(), output.value(), epsilon = 1e-12);
+
+        let row = autograd::grad(&[output], &inputs, false);
+        let expected: f64 = row.iter().zip(&direction).map(|(g, v)| g.value() * v).sum();
+        assert_abs_diff_eq!(tangent, expected, epsilon = 1e-12);
+    }
+}
+
+#[test]
+fn valid_jvp_under_no_grad() {
+    let model = sequential!(
+        Ix1,
+        [
+            Linear::new("fc1", 3, 4),
+            Activation::Tanh,
+            Linear::new("fc2", 4, 2)
+        ]
+    );
+
+    let input = tensor![0.5, -1.0, 2.0];
+    let direction = array![1.0, 0.5, -2.0];
+
+    let (_, expected) = autograd::jvp(|x| model.forward(x), &input, &direction);
+    let (outputs, jvp) = {
+        let _guard = no_grad();
+        autograd::jvp(|x| model.forward(x), &input, &direction)
+    };
+
+    assert!(outputs.iter().all(|output| !output.should_compute_grad()));
+    for (tangent, expected) in jvp.iter().zip(&expected) {
+        assert_abs_diff_eq!(tangent, expected, epsilon = 1e-12);
+    }
+}
+
+#[test]
+fn valid_directional_derivative_with_respect_to_parameter() {
+    let linear = Linear::new("fc", 2, 2);
+    let input = tensor![3.0, -4.0];
+
+    linear.weights[[1, 0]].set_tangent(Some(1.0));
+    let tangents = linear.forward(&input).tangents();
+    linear.weights[[1, 0]].set_tangent(None);
+
+    assert_eq!(tangents, array![0.0, 3.0]);
+    assert!(linear.forward(&input).tangents().iter().all(|t| *t == 0.0));
+}
@@ -0,0 +1,59 @@
+extern crate micrograd_rs;
+use micrograd_rs::autograd;
+use micrograd_rs::criterions::{Criterion, Reduction, MSE};
+use micrograd_rs::prelude::*;
+use micrograd_rs::{Layer, Linear};
+
+use crate::common::assert_all_close;
+
+#[test]
+fn valid_jacobian_of_vector_function() {
+    let inputs = values![2.0, 3.0];
+
+    let jacobian = autograd::jacobian(|v| vec![&v[0] * &v[1], v[0].sin(), v[1].powf(2.0)], &inputs);
+
+    let expected = array![[3.0, 2.0], [2.0_f64.cos(), 0.0], [0.0, 6.0]];
+    assert_all_close(&jacobian, &expected, 1e-12);
+    assert!(inputs.iter().all(|input| input.grad().is_none()));
+}
+
+#[test]
+fn valid_hessian_of_rosenbrock() {
+    let inputs = values![1.5, -0.5];
+
+    let hessian = autograd::hessian(
+        |v| (v[0].clone() - 1.0).powf(2.0) + (v[1].clone() - v[0].powf(2.0)).powf(2.0) * 100.0,
+        &inputs,
+    );
+
+    let (x, y) = (1.5, -0.5);
+    let expected = array![
+        [1200.0 * x * x - 400.0 * y + 2.0, -400.0 * x],
+        [-400.0 * x, 200.0]
+    ];
+    assert_all_close(&hessian, &expected, 1e-9);
+}
+
+#[test]
+fn valid_hessian_of_linear_least_squares() {
+    let linear = Linear::new("fc", 2, 1);
+    let inputs = tensor![[1.0, 2.0], [-1.0, 0.5], [3.0, -2.0]];
+    let targets = tensor!([[1.0], [0.0], [2.0]], requires_grad = false);
+
+    let params: Vec<Value> = linear
+        .weights
+        .iter()
+        .chain(&linear.biases)
+        .cloned()
+        .collect();
+    let hessian = autograd::hessian(
+        |_| MSE::loss(Reduction::Mean, &linear.forward(&inputs), &targets),
+        &params,
+    );
+
+    // The loss is quadratic in [w1, w2, b], so its Hessian is 2/N * X^T X
+    // with a column of ones appended to X.
+    let design = array![[1.0, 2.0, 1.0], [-1.0, 0.5, 1.0], [3.0, -2.0, 1.0]];
+    let expected = design.t().dot(&design) * (2.0 / 3.0);
+    assert_all_close(&hessian, &expected, 1e-12);
+}
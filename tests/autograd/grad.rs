@@ -0,0 +1,70 @@
+extern crate micrograd_rs;
+use approx::assert_abs_diff_eq;
+use micrograd_rs::autograd;
+use micrograd_rs::prelude::*;
+use std::slice;
+
+#[test]
+fn valid_grad_leaves_other_leaves_untouched() {
+    let x = Value::from(3.0);
+    let w = Value::from(-2.0);
+    let y = x.powf(2.0) * w.clone();
+
+    let grads = autograd::grad(&[y], slice::from_ref(&x), false);
+
+    assert_eq!(grads.len(), 1);
+    assert_eq!(grads[0].value(), -12.0);
+    assert!(x.grad().is_none());
+    assert!(w.grad().is_none());
+}
+
+#[test]
+fn valid_grad_of_multiple_outputs() {
+    let x = Value::from(3.0);
+    let y = Value::from(4.0);
+    let unused = Value::from(5.0);
+
+    let outputs = [&x * &y, x.powf(2.0)];
+    let grads = autograd::grad(&outputs, &[x, y, unused], false);
+    let grads = grads.iter().map(Value::value).collect::<Vec<f64>>();
+
+    assert_eq!(grads, [10.0, 3.0, 0.0]);
+}
+
+#[test]
+fn valid_grad_higher_order_derivatives() {
+    let x = Value::from(3.0);
+    let y = x.powf(4.0);
+
+    let first = autograd::grad(&[y], slice::from_ref(&x), true).remove(0);
+    let second = autograd::grad(slice::from_ref(&first), slice::from_ref(&x), true).remove(0);
+    let third = autograd::grad(slice::from_ref(&second), slice::from_ref(&x), false).remove(0);
+
+    assert_eq!(first.value(), 108.0);
+    assert_eq!(second.value(), 108.0);
+    assert_eq!(third.value(), 72.0);
+}
+
+#[test]
+fn valid_hessian_vector_product() {
+    let (a, b) = (Value::from(1.5), Value::from(-0.5));
+    let f = a.powf(2.0) * b.clone() + b.powf(3.0);
+
+    let inputs = [a.clone(), b.clone()];
+    let grads = autograd::grad(&[f], &inputs, true);
+
+    let v = [0.3, -1.2];
+    let grad_dot_v = grads
+        .iter()
+        .zip(v)
+        .map(|(grad, v)| grad * &v)
+        .sum::<Value>();
+    let hvp = autograd::grad(&[grad_dot_v], &inputs, false);
+
+    let (a, b) = (a.value(), b.value());
+    let hessian = [[2.0 * b, 2.0 * a], [2.0 * a, 6.0 * b]];
+    for (row, hv) in hessian.iter().zip(hvp) {
+        let actual = row[0] * v[0] + row[1] * v[1];
+        assert_abs_diff_eq!(hv.value(), actual, epsilon = 1e-12);
+    }
+}
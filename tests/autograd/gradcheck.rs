@@ -0,0 +1,85 @@
+extern crate micrograd_rs;
+use micrograd_rs::activations as Activation;
+use micrograd_rs::autograd::{self, GradMismatch};
+use micrograd_rs::prelude::*;
+use micrograd_rs::{BatchNorm, Conv2D, Layer, Linear};
+
+const EPS: f64 = 1e-6;
+const TOL: f64 = 1e-6;
+
+fn smooth(inputs: &[Value]) -> Value {
+    let (x, y, z) = (&inputs[0], &inputs[1], &inputs[2]);
+    (x * y).tanh() + (z / y).exp() - x.powf(3.0) * z.sigmoid() + y.softplus().log()
+}
+
+#[test]
+fn valid_gradcheck_of_smooth_function() {
+    let inputs = values![0.7, -1.3, 0.4];
+
+    assert_eq!(autograd::gradcheck(smooth, &inputs, EPS, TOL), Ok(()));
+    assert!(inputs.iter().all(|input| input.grad().is_none()));
+    assert_eq!(inputs, values![0.7, -1.3, 0.4]);
+}
+
+#[test]
+fn valid_gradcheck_reports_worst_offending_input() {
+    // Correct for the first operand, off by a factor of two for the second.
+    let faulty_mul = |inputs: &[Value]| {
+        let value = inputs[0].value() * inputs[1].value();
+        Value::custom(value, inputs.to_vec(), |operands, _, grad| {
+            vec![grad * &operands[1], grad * &(&operands[0] * &2.0)]
+        })
+    };
+
+    let inputs = values![3.0, 5.0];
+    let mismatch = autograd::gradcheck(faulty_mul, &inputs, EPS, TOL).unwrap_err();
+
+    assert_eq!(mismatch.input, 1);
+    assert_eq!(mismatch.analytical, 6.0);
+    assert!((mismatch.numerical - 3.0).abs() < 1e-6);
+}
+
+#[test]
+fn valid_gradcheck_mismatch_display() {
+    let mismatch = GradMismatch {
+        input: 2,
+        analytical: 1.0,
+        numerical: 0.5,
+        error: 0.5,
+    };
+
+    assert_eq!(
+        mismatch.to_string(),
+        "Gradient mismatch for input 2: analytical 1, numerical 0.5 (error 0.5)"
+    );
+}
+
+#[test]
+fn valid_gradcheck_of_layers() {
+    let linear = Linear::new("fc", 3, 2);
+    let input = tensor![[0.5, -1.0, 2.0], [1.5, 0.3, -0.7]];
+    assert_eq!(autograd::gradcheck_layer(&linear, &input, EPS, TOL), Ok(()));
+
+    let softmax = Activation::Softmax(1);
+    assert_eq!(
+        autograd::gradcheck_layer(&softmax, &input, EPS, TOL),
+        Ok(())
+    );
+
+    let batch_norm = BatchNorm::new("bn", 2);
+    let input = tensor![[[0.5, -1.0, 2.0], [1.5, 0.3, -0.7]]];
+    assert_eq!(
+        autograd::gradcheck_layer(&batch_norm, &input, EPS, TOL),
+        Ok(())
+    );
+    assert_eq!(
+        Layer::<Ix3, Ix3>::buffers(&batch_norm),
+        vec![0.0, 0.0, 1.0, 1.0]
+    );
+
+    let conv: Conv2D = Conv2D::new("conv", 2, 1, (2, 2), (1, 0), (1, 1), (1, 1));
+    let input = Tensor::from_shape_fn((1, 2, 3, 3), |(_, c, h, w)| {
+        val!((c as f64 - 0.5) * (h as f64 + 1.0) - 0.3 * w as f64)
+    });
+    assert_eq!(autograd::gradcheck_layer(&conv, &input, EPS, TOL), Ok(()));
+}
@@ -0,0 +1,101 @@
+extern crate micrograd_rs;
+use micrograd_rs::prelude::*;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn valid_hook_clips_grad() {
+    let x = Value::from(3.0);
+    x.register_hook(|grad| Some(val!(grad.value().clamp(-1.0, 1.0))));
+
+    let y = &x * &5.0;
+    y.backward();
+
+    assert_eq!(x.grad().unwrap().value(), 1.0);
+}
+
+#[test]
+fn valid_hook_observes_grad_without_changing_it() {
+    let seen = Arc::new(Mutex::new(vec![]));
+    let x = Value::from(2.0);
+    let y = &x * &x;
+
+    let log = seen.clone();
+    y.register_hook(move |grad| {
+        log.lock().unwrap().push(grad.value());
+        None
+    });
+    let z = &y * &3.0;
+    z.backward();
+
+    assert_eq!(*seen.lock().unwrap(), vec![3.0]);
+    assert_eq!(x.grad().unwrap().value(), 12.0);
+}
+
+#[test]
+fn valid_hook_on_intermediate_reverses_grad() {
+    let x = Value::from(2.0);
+    let features = &x * &4.0;
+    features.register_hook(|grad| Some(-grad));
+    features.retain_grad();
+
+    let loss = features.powf(2.0);
+    loss.backward();
+
+    assert_eq!(features.grad().unwrap().value(), -16.0);
+    assert_eq!(x.grad().unwrap().value(), -64.0);
+}
+
+#[test]
+fn valid_hooks_run_in_registration_order() {
+    let x = Value::from(1.0);
+    x.register_hook(|grad| Some(grad * &2.0));
+    x.register_hook(|grad| Some(grad + &1.0));
+
+    let y = &x * &3.0;
+    y.backward();
+
+    assert_eq!(x.grad().unwrap().value(), 7.0);
+}
+
+#[test]
+fn valid_hook_sees_complete_grad() {
+    let calls = Arc::new(Mutex::new(0));
+    let x = Value::from(2.0);
+
+    let counter = calls.clone();
+    x.register_hook(move |grad| {
+        *counter.lock().unwrap() += 1;
+        assert_eq!(grad.value(), 5.0);
+        None
+    });
+    let y = &(&x * &2.0) + &(&x * &3.0);
+    y.backward();
+
+    assert_eq!(*calls.lock().unwrap(), 1);
+}
+
+#[test]
+fn valid_hook_keeps_graph_when_creating_graph() {
+    let x = Value::from(3.0);
+    x.register_hook(|grad| Some(grad * &2.0));
+
+    let y = x.powf(3.0);
+    y.backward_with(true);
+    let grad = x.grad().unwrap();
+
+    assert_eq!(grad.value(), 54.0);
+    assert!(!grad.is_leaf());
+}
+
+#[test]
+fn valid_tensor_hook_applies_to_every_element() {
+    let weights = tensor![[1.0, -2.0], [3.0, 0.5]];
+    weights.register_hook(|grad| Some(val!(grad.value().clamp(-1.0, 1.0))));
+
+    let loss: Value = weights.iter().map(|w| w * &4.0).sum();
+    loss.backward();
+
+    for w in weights.iter() {
+        assert_eq!(w.grad().unwrap().value(), 1.0);
+    }
+}
@@ -0,0 +1,49 @@
+mod accumulation;
+mod anomaly;
+mod capture;
+mod custom_op;
+mod detach;
+mod dot;
+mod forward;
+mod functional;
+mod grad;
+mod gradcheck;
+mod hooks;
+mod no_grad;
+mod precision;
+mod profiler;
+#[cfg(feature = "tape")]
+mod tape;
+
+use micrograd_rs::activations as Activation;
+use micrograd_rs::prelude::*;
+use micrograd_rs::{Layer, Linear, Sequential};
+
+fn build_model() -> Sequential<Ix2> {
+    sequential!(
+        Ix2,
+        [
+            Linear::new("fc1", 3, 4),
+            Activation::Tanh,
+            Linear::new("fc2", 4, 2),
+            Activation::Softmax(1)
+        ]
+    )
+}
+
+fn batches() -> Vec<(Array2<f64>, Array2<f64>)> {
+    vec![
+        (
+            array![[2.0, 3.0, -1.0], [3.0, -1.0, 0.5]],
+            array![[1.0, 0.0], [0.0, 1.0]],
+        ),
+        (
+            array![[0.5, 1.0, 1.0], [1.0, 1.0, -1.0]],
+            array![[0.0, 1.0], [1.0, 0.0]],
+        ),
+        (
+            array![[-2.0, 0.5, 4.0], [0.0, -3.0, 1.5]],
+            array![[0.0, 1.0], [0.0, 1.0]],
+        ),
+    ]
+}
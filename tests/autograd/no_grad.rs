@@ -0,0 +1,70 @@
+extern crate micrograd_rs;
+use micrograd_rs::activations as Activation;
+use micrograd_rs::autograd::{is_grad_enabled, no_grad};
+use micrograd_rs::prelude::*;
+use micrograd_rs::{Layer, Linear, Sequential};
+
+#[test]
+fn valid_no_grad_builds_no_graph() {
+    let x = Value::from(3.0);
+
+    let expected = ((&x * &2.0).exp() + x.clone()).value();
+    let y = {
+        let _guard = no_grad();
+        (&x * &2.0).exp() + x.clone()
+    };
+    y.backward();
+
+    assert_eq!(y.value(), expected);
+    assert!(x.grad().is_none());
+    assert!(!y.should_compute_grad());
+}
+
+#[test]
+fn valid_no_grad_guard_restores_grad_mode() {
+    assert!(is_grad_enabled());
+
+    {
+        let _outer = no_grad();
+        {
+            let _inner = no_grad();
+            assert!(!is_grad_enabled());
+        }
+        assert!(!is_grad_enabled());
+    }
+
+    assert!(is_grad_enabled());
+
+    let x = Value::from(3.0);
+    let y = &x * &2.0;
+    y.backward();
+
+    assert_eq!(x.grad().unwrap().value(), 2.0);
+}
+
+#[test]
+fn valid_no_grad_sequential_forward() {
+    let model = sequential!(
+        Ix1,
+        [
+            Linear::new("fc1", 3, 4),
+            Activation::Tanh,
+            Linear::new("fc2", 4, 1),
+            Activation::Sigmoid
+        ]
+    );
+    let input = tensor![2.0, 3.0, -1.0];
+
+    let expected = model.forward(&input).mapv(|v| v.value());
+    let output = {
+        let _guard = no_grad();
+        model.forward(&input)
+    };
+
+    assert_eq!(output.mapv(|v| v.value()), expected);
+
+    output.sum().backward();
+    for param in model.parameters() {
+        assert!(param.grad().is_none());
+    }
+}
@@ -0,0 +1,105 @@
+extern crate micrograd_rs;
+use approx::assert_abs_diff_eq;
+use micrograd_rs::activations as Activation;
+use micrograd_rs::criterions::{Criterion, Reduction, MSE};
+use micrograd_rs::optim::{Optimizer, SGD};
+use micrograd_rs::prelude::*;
+use micrograd_rs::{Layer, Linear, Model, Sequential};
+use std::fs;
+
+#[test]
+fn valid_f32_backward() {
+    let x = Value::new(2.0f32);
+    let y = Value::new(-3.0f32);
+
+    let z = (&x * &y + x.exp()).tanh() + y.powf(2.0);
+    z.backward();
+
+    let x64 = val!(2.0);
+    let y64 = val!(-3.0);
+
+    let z64 = (&x64 * &y64 + x64.exp()).tanh() + y64.powf(2.0);
+    z64.backward();
+
+    assert_abs_diff_eq!(z.value() as f64, z64.value(), epsilon = 1e-5);
+    assert_abs_diff_eq!(
+        x.grad().unwrap().value() as f64,
+        x64.grad().unwrap().value(),
+        epsilon = 1e-5
+    );
+    assert_abs_diff_eq!(
+        y.grad().unwrap().value() as f64,
+        y64.grad().unwrap().value(),
+        epsilon = 1e-5
+    );
+}
+
+#[test]
+fn valid_f32_macros() {
+    let x = val!(1.5; f32, requires_grad = true);
+    let xs: Tensor<Ix1, f32> = tensor!([1.0, 2.0]; f32, requires_grad = true);
+
+    let y = x.clone() * 2 + xs.sum() * 3;
+    y.backward();
+
+    assert_eq!(y.value(), 12.0f32);
+    assert_eq!(x.grad().unwrap().value(), 2.0f32);
+    assert_eq!(xs.grads(), array![3.0f32, 3.0]);
+}
+
+#[test]
+fn valid_f32_training_step() {
+    let model: Sequential<Ix1, f32> = sequential!(
+        Ix1,
+        f32,
+        [
+            Linear::new("fc1", 3, 4),
+            Activation::Tanh,
+            Linear::new("fc2", 4, 1)
+        ]
+    );
+
+    let mut optimizer = SGD {
+        params: model.parameters().to_vec(),
+        lr: Value::new(0.1f32),
+        ..Default::default()
+    };
+
+    let input = tensor!([2.0, 3.0, -1.0]; f32);
+    let target = tensor!([1.0]; f32);
+
+    let loss = MSE::loss(Reduction::Mean, &model.forward(&input), &target);
+    loss.backward();
+
+    let before = model.parameters().mapv(|p| p.value());
+    optimizer.step();
+    let after = model.parameters().mapv(|p| p.value());
+
+    for (param, (old, new)) in model.parameters().iter().zip(before.iter().zip(&after)) {
+        let step = 0.1 * param.grad().unwrap().value();
+        assert_abs_diff_eq!(old - new, step, epsilon = 1e-6);
+    }
+}
+
+#[test]
+fn valid_f32_state_dict_round_trip() {
+    let build = || -> Sequential<Ix1, f32> {
+        sequential!(Ix1, f32, [Linear::new("fc1", 5, 3), Activation::ReLU])
+    };
+
+    let model1 = build();
+    let mut model2 = build();
+
+    assert_ne!(model1.state_dict(), model2.state_dict());
+
+    let path = "linear_f32.pickle";
+    model1.save_state_dict(path);
+    model2.load_state_dict(path);
+
+    assert_eq!(model1.state_dict(), model2.state_dict());
+
+    assert!(
+        fs::remove_file(path).is_ok(),
+        "File \"linear_f32.pickle\" could not be removed."
+    );
+}
@@ -0,0 +1,102 @@
+extern crate micrograd_rs;
+use micrograd_rs::activations as Activation;
+use micrograd_rs::autograd::{is_profiling, live_nodes, profile};
+use micrograd_rs::prelude::*;
+use micrograd_rs::{Layer, Linear, Sequential};
+
+#[test]
+fn valid_op_counts() {
+    let x = val!(2.0);
+    let y = val!(-3.0);
+
+    let profile = profile();
+    let z = &x * &y + x.exp() * 2.0;
+    let stats = profile.stats();
+
+    assert_eq!(stats.nodes, 5);
+    assert_eq!(stats.ops["Mul"], 2);
+    assert_eq!(stats.ops["Exp"], 1);
+    assert_eq!(stats.ops["Add"], 1);
+    assert!(!stats.ops.contains_key("Sub"));
+    assert!(live_nodes() >= 5);
+    assert!(stats.peak_live_nodes >= 5);
+    assert_eq!(stats.backward_calls, 0);
+
+    z.backward();
+    z.backward();
+    assert_eq!(profile.stats().backward_calls, 2);
+}
+
+#[test]
+fn valid_fused_matmul_counts() {
+    let a = Tensor::from_shape_simple_fn((2, 3), || val!(1.0));
+    let b = Tensor::from_shape_simple_fn((3, 4), || val!(1.0));
+
+    let profile = profile();
+    let _product = a.dot(&b);
+    let stats = profile.stats();
+
+    assert_eq!(stats.ops["MatMul"], 1);
+    assert_eq!(stats.ops["MatMulElement"], 8);
+    assert_eq!(stats.nodes, 8);
+}
+
+#[test]
+fn valid_layer_breakdown() {
+    let model: Sequential<Ix1> = sequential!(
+        Ix1,
+        [
+            Linear::new("fc1", 3, 4),
+            Activation::Tanh,
+            Linear::new("fc2", 4, 1)
+        ]
+    );
+    let input = tensor![2.0, 3.0, -1.0];
+
+    let profile = profile();
+    model.forward(&input);
+    model.forward(&input);
+    let stats = profile.stats();
+
+    let layers = stats.layers.keys().cloned().collect::<Vec<String>>();
+    assert_eq!(layers, vec!["fc1", "Tanh", "fc2"]);
+
+    let tanh = &stats.layers["Tanh"];
+    assert_eq!(tanh.calls, 2);
+    assert_eq!(tanh.nodes, 8);
+    assert_eq!(stats.ops["Tanh"], 8);
+
+    let layer_nodes = stats
+        .layers
+        .values()
+        .map(|layer| layer.nodes)
+        .sum::<usize>();
+    assert_eq!(layer_nodes, stats.nodes);
+    assert!(stats.layers["fc1"].nodes > stats.layers["fc2"].nodes);
+    assert!(stats.to_string().contains("fc1"));
+}
+
+#[test]
+fn valid_nested_profiles() {
+    assert!(!is_profiling());
+
+    let outer = profile();
+    let _ = val!(1.0) + val!(2.0);
+    let inner_stats = {
+        let inner = profile();
+        let z = val!(1.0) * val!(2.0);
+        assert_eq!(inner.stats().nodes, 3);
+        assert!(!inner.stats().ops.contains_key("Add"));
+        z.backward();
+        inner.stats()
+    };
+
+    let stats = outer.stats();
+    assert_eq!(stats.nodes, 3 + inner_stats.nodes);
+    assert_eq!(stats.ops["Add"], 1);
+    assert_eq!(stats.ops["Mul"], 1);
+    assert_eq!(stats.backward_calls, 1);
+
+    drop(outer);
+    assert!(!is_profiling());
+}
@@ -0,0 +1,248 @@
+extern crate micrograd_rs;
+use approx::assert_abs_diff_eq;
+use micrograd_rs::activations as Activation;
+use micrograd_rs::autograd::{self, is_recording, profile, tape};
+use micrograd_rs::criterions::{Criterion, Reduction, MSE};
+use micrograd_rs::prelude::*;
+use micrograd_rs::{BatchNorm, Conv1D, Conv2D, Layer, Linear, Sequential};
+use std::panic::{self, AssertUnwindSafe};
+
+use super::build_model;
+
+#[test]
+fn valid_tape_grads_match_graph_grads() {
+    let model = build_model();
+    let inputs = tensor![[2.0, 3.0, -1.0], [3.0, -1.0, 0.5]];
+    let targets = tensor!([[1.0, 0.0], [0.0, 1.0]], requires_grad = false);
+
+    let loss = MSE::loss(Reduction::Mean, &model.forward(&inputs), &targets);
+    loss.backward();
+    let graph_grads = model.parameters().grads();
+
+    model.parameters().zero_grad();
+
+    let tape_loss = {
+        let _tape = tape();
+        let loss = MSE::loss(Reduction::Mean, &model.forward(&inputs), &targets);
+        loss.backward();
+        loss.value()
+    };
+    let tape_grads = model.parameters().grads();
+
+    assert_eq!(tape_loss, loss.value());
+    for (tape_grad, graph_grad) in tape_grads.into_iter().zip(graph_grads) {
+        assert_abs_diff_eq!(tape_grad, graph_grad, epsilon = 1e-12);
+    }
+}
+
+#[test]
+fn valid_tape_grads_of_conv2d() {
+    let conv: Conv2D = Conv2D::new("conv2d", 2, 3, (2, 2), (1, 0), (1, 1), (1, 1));
+    let input = Tensor::from_shape_fn((2, 2, 4, 3), |(b, c, h, w)| {
+        val!((b as f64 - 0.5) * (c + h) as f64 - 0.25 * w as f64)
+    });
+
+    conv.forward(&input).sum().backward();
+    let graph_grads = conv.parameters().grads();
+
+    conv.parameters().zero_grad();
+    {
+        let _tape = tape();
+        conv.forward(&input).sum().backward();
+    }
+
+    for (tape_grad, graph_grad) in conv.parameters().grads().into_iter().zip(graph_grads) {
+        assert_abs_diff_eq!(tape_grad, graph_grad, epsilon = 1e-12);
+    }
+}
+
+#[test]
+fn valid_tape_iterations_through_batch_norm() {
+    let model: Sequential<Ix3> = sequential!(
+        Ix3,
+        [
+            Conv1D::new("conv1d", 2, 2, 2, 0, 1, 1),
+            BatchNorm::new("bn", 2),
+            Activation::Tanh
+        ]
+    );
+    let input = Tensor::from_shape_fn((1, 2, 5), |(_, c, w)| val!(c as f64 - 0.5 * w as f64));
+
+    for _ in 0..3 {
+        let params = model.parameters();
+
+        params.zero_grad();
+        model.forward(&input).sum().backward();
+        let graph_grads = params.grads();
+
+        params.zero_grad();
+        {
+            let _tape = tape();
+            model.forward(&input).sum().backward();
+        }
+
+        for (tape_grad, graph_grad) in params.grads().iter().zip(&graph_grads) {
+            assert_abs_diff_eq!(tape_grad, graph_grad, epsilon = 1e-12);
+        }
+    }
+}
+
+#[test]
+fn valid_tape_backward_reaches_graph_nodes() {
+    let x = Value::from(0.5);
+    let y = &x * &x;
+
+    {
+        let _tape = tape();
+        let z = y.exp() * 3.0;
+        z.backward();
+    }
+
+    let expected = 3.0 * 0.25_f64.exp() * 2.0 * 0.5;
+    assert_abs_diff_eq!(x.grad().unwrap().value(), expected, epsilon = 1e-12);
+    assert!(y.grad().is_none());
+}
+
+#[test]
+fn valid_tape_with_custom_op() {
+    let square = |x: &Value| {
+        Value::custom(x.value().powi(2), vec![x.clone()], |operands, _, grad| {
+            vec![grad * &(&operands[0] * &2.0)]
+        })
+    };
+
+    let x = Value::from(3.0);
+    {
+        let _tape = tape();
+        let y = square(&(&x + &1.0)).sqrt();
+        assert_eq!(y.value(), 4.0);
+        y.backward();
+    }
+
+    assert_abs_diff_eq!(x.grad().unwrap().value(), 1.0, epsilon = 1e-12);
+}
+
+#[test]
+fn valid_tape_guard_nesting() {
+    assert!(!is_recording());
+    {
+        let _outer = tape();
+        let x = Value::from(2.0) * 3.0;
+        {
+            let _inner = tape();
+            assert!(is_recording());
+        }
+        assert!(is_recording());
+        assert_eq!(x.value(), 6.0);
+    }
+    assert!(!is_recording());
+}
+
+#[test]
+#[should_panic(expected = "Tape value used after its tape was dropped")]
+fn invalid_tape_value_after_guard_dropped() {
+    let x = Value::from(2.0);
+    let y = {
+        let _tape = tape();
+        &x * &x
+    };
+
+    y.value();
+}
+
+#[test]
+fn valid_no_grad_values_outlive_tape() {
+    let x = Value::from(2.0);
+    let y = {
+        let _tape = tape();
+        let _no_grad = autograd::no_grad();
+        &x * &x + 1.0
+    };
+
+    assert_eq!(y.value(), 5.0);
+    assert!(!y.should_compute_grad());
+}
+
+#[cfg(feature = "sync")]
+#[test]
+fn invalid_tape_value_on_another_thread() {
+    let x = Value::from(2.0);
+    let _tape = tape();
+    let y = &x * &x;
+
+    let result = std::thread::spawn(move || {
+        let _tape = tape();
+        y.value()
+    })
+    .join();
+
+    let panic = result.unwrap_err();
+    let message = panic.downcast_ref::<&str>().unwrap();
+    assert!(message.starts_with("Tape value used after its tape was dropped"));
+}
+
+fn assert_rejected(method: &str, call: impl FnOnce()) {
+    let panic = panic::catch_unwind(AssertUnwindSafe(call)).unwrap_err();
+    let message = panic.downcast_ref::<String>().unwrap();
+
+    assert!(
+        message.starts_with(&format!("Value::{method} is not supported on tape values")),
+        "{method}: {message}"
+    );
+}
+
+#[test]
+fn invalid_value_methods_on_tape_values() {
+    let x = Value::from(2.0);
+    let _tape = tape();
+    let y = &x * &x;
+
+    assert_rejected("value_mut", || *y.value_mut() = 1.0);
+    assert_rejected("requires_grad", || y.clone().requires_grad(false));
+    assert_rejected("register_hook", || y.register_hook(|_| None));
+    assert_rejected("retain_grad", || y.retain_grad());
+    assert_rejected("set_tangent", || y.set_tangent(Some(1.0)));
+    assert_rejected("grad_mut", || *y.grad_mut() = val!(1.0));
+}
+
+#[test]
+#[should_panic(expected = "autograd::grad does not support tape values")]
+fn invalid_grad_of_tape_value() {
+    let x = Value::from(2.0);
+    let _tape = tape();
+    let y = &x * &x;
+
+    autograd::grad(&[y], &[x], false);
+}
+
+#[test]
+#[should_panic(expected = "Value::to_dot does not support tape values")]
+fn invalid_dot_of_tape_value() {
+    let x = Value::from(2.0);
+    let _tape = tape();
+
+    (&x * &x).to_dot();
+}
+
+#[test]
+fn valid_tape_counts() {
+    let model: Sequential<Ix1> = sequential!(Ix1, [Linear::new("fc1", 3, 4), Activation::Tanh]);
+    let input = tensor![2.0, 3.0, -1.0];
+
+    let _tape = tape();
+    let profile = profile();
+    model.forward(&input);
+    let stats = profile.stats();
+
+    assert_eq!(stats.ops["Tanh"], 4);
+    assert_eq!(stats.layers["Tanh"].nodes, 4);
+    assert!(stats.layers["fc1"].nodes > 0);
+    assert_eq!(
+        stats.nodes,
+        stats
+            .layers
+            .values()
+            .map(|layer| layer.nodes)
+            .sum::<usize>()
+    );
+}
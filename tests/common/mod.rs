@@ -0,0 +1,13 @@
+use approx::assert_abs_diff_eq;
+use micrograd_rs::prelude::*;
+
+pub fn assert_all_close<D: Dimension>(
+    actual: &Array<f64, D>,
+    expected: &Array<f64, D>,
+    epsilon: f64,
+) {
+    assert_eq!(actual.shape(), expected.shape());
+    for (actual, expected) in actual.iter().zip(expected) {
+        assert_abs_diff_eq!(actual, expected, epsilon = epsilon);
+    }
+}
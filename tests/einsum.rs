@@ -0,0 +1,102 @@
+extern crate micrograd_rs;
+use micrograd_rs::autograd::profile;
+use micrograd_rs::prelude::*;
+
+#[test]
+fn valid_batched_matmul() {
+    let a = Array::from_shape_fn((2, 3, 4), |(b, i, j)| (b * 12 + i * 4 + j) as f64 - 10.0);
+    let b = Array::from_shape_fn((2, 4, 2), |(b, j, k)| (b + j * k) as f64 * 0.5);
+    let (a_tensor, b_tensor) = (a.mapv(Value::new), b.mapv(Value::new));
+
+    let profile = profile();
+    let product: Tensor<Ix3> = einsum("bij,bjk->bik", &[&a_tensor, &b_tensor]);
+    assert_eq!(profile.stats().ops["MatMul"], 2);
+
+    for batch in 0..2 {
+        let expected = a
+            .index_axis(Axis(0), batch)
+            .dot(&b.index_axis(Axis(0), batch));
+        assert_eq!(product.values().index_axis(Axis(0), batch), expected);
+    }
+}
+
+#[test]
+fn valid_permuted_contraction_grads() {
+    let a = array![[1.0, -2.0, 3.0], [0.5, 4.0, -1.0]];
+    let b = array![[2.0, 1.0, 0.0], [-1.0, 3.0, -2.0]];
+
+    let (a_einsum, b_einsum) = (a.mapv(Value::new), b.mapv(Value::new));
+    let product: Tensor<Ix2> = einsum("ik,jk->ji", &[&a_einsum, &b_einsum]);
+    assert_eq!(product.values(), b.dot(&a.t()));
+    product.mapv(|x| x.powf(2.0)).sum().backward();
+
+    let (a_dot, b_dot) = (a.mapv(Value::new), b.mapv(Value::new));
+    let expected = b_dot.dot(&a_dot.t().to_owned());
+    expected.mapv(|x| x.powf(2.0)).sum().backward();
+
+    assert_eq!(a_einsum.grads(), a_dot.grads());
+    assert_eq!(b_einsum.grads(), b_dot.grads());
+}
+
+#[test]
+fn valid_bilinear_form() {
+    let x = tensor![1.0, -1.0];
+    let w = tensor![[2.0, 0.5, 1.0], [3.0, -1.0, 4.0]];
+    let y = tensor![0.5, 2.0, -1.0];
+
+    let form: Tensor<Ix0> = einsum("i,ij,j->", &[&x, &w, &y]);
+    assert_eq!(form.into_scalar().value(), 5.5);
+}
+
+#[test]
+fn valid_matrix_vector_product() {
+    let w = tensor![[2.0, 0.5, 1.0], [3.0, -1.0, 4.0]];
+    let y = tensor![0.5, 2.0, -1.0];
+
+    let product: Tensor<Ix1> = einsum("ij,j->i", &[&w, &y.view()]);
+    assert_eq!(product.values(), array![1.0, -4.5]);
+}
+
+#[test]
+fn valid_implicit_output_and_diagonal() {
+    let a = tensor![[1.0, 2.0], [3.0, 4.0]];
+    let b = tensor![[0.0, 1.0], [-1.0, 2.0]];
+
+    let product: Tensor<Ix2> = einsum("ij,jk", &[&a, &b]);
+    assert_eq!(product.values(), a.values().dot(&b.values()));
+
+    let transposed: Tensor<Ix2> = einsum("ji", &[&a]);
+    assert_eq!(transposed.values(), a.values().t());
+
+    let trace: Tensor<Ix0> = einsum("ii->", &[&a]);
+    let trace = trace.into_scalar();
+    assert_eq!(trace.value(), 5.0);
+
+    trace.backward();
+    assert_eq!(a.grads(), array![[1.0, 0.0], [0.0, 1.0]]);
+}
+
+#[test]
+#[should_panic(expected = "'j' has length 3 in operand 1 but 2 elsewhere")]
+fn invalid_mismatched_lengths() {
+    let a = tensor![[1.0, 2.0], [3.0, 4.0]];
+    let b = tensor![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]];
+
+    let _: Tensor<Ix2> = einsum("ij,jk->ik", &[&a, &b]);
+}
+
+#[test]
+#[should_panic(expected = "operand 0 has 2 axes but \"ijk\" names 3")]
+fn invalid_subscript_count() {
+    let a = tensor![[1.0, 2.0], [3.0, 4.0]];
+
+    let _: Tensor<Ix2> = einsum("ijk->ij", &[&a]);
+}
+
+#[test]
+#[should_panic(expected = "output 'k' does not appear in any input")]
+fn invalid_output_subscript() {
+    let a = tensor![[1.0, 2.0], [3.0, 4.0]];
+
+    let _: Tensor<Ix1> = einsum("ij->k", &[&a]);
+}
@@ -2,3 +2,5 @@ mod batch_normalization;
 mod convolution;
 mod model;
 mod pooling;
+#[cfg(feature = "sync")]
+mod sync;
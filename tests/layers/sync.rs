@@ -0,0 +1,100 @@
+extern crate micrograd_rs;
+use micrograd_rs::activations as Activation;
+use micrograd_rs::criterions::{Criterion, Reduction, MSE};
+use micrograd_rs::optim::{Optimizer, SGD};
+use micrograd_rs::prelude::*;
+use micrograd_rs::{Layer, Linear, Sequential};
+use std::sync::Arc;
+use std::thread;
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+fn model() -> Sequential<Ix1> {
+    sequential!(
+        Ix1,
+        [
+            Linear::new("fc1", 3, 4),
+            Activation::Tanh,
+            Linear::new("fc2", 4, 1)
+        ]
+    )
+}
+
+#[test]
+fn valid_types_are_send_and_sync() {
+    assert_send_sync::<Value>();
+    assert_send_sync::<Tensor<Ix2>>();
+    assert_send_sync::<Sequential<Ix1>>();
+    assert_send_sync::<SGD>();
+}
+
+#[test]
+fn valid_sequential_moves_to_another_thread() {
+    let model = model();
+    let input = tensor!([0.5, -1.0, 2.0], requires_grad = false);
+    let expected = model.forward(&input)[0].value();
+
+    let (model, output) = thread::spawn(move || {
+        let output = model.forward(&input)[0].value();
+        (model, output)
+    })
+    .join()
+    .unwrap();
+
+    assert_eq!(output, expected);
+    assert_eq!(model.forward(&tensor![0.5, -1.0, 2.0])[0].value(), expected);
+}
+
+#[test]
+fn valid_training_step_on_another_thread() {
+    let model = model();
+    let before = model.parameters().mapv(|param| param.value());
+
+    let model = thread::spawn(move || {
+        let mut optimizer = SGD {
+            params: model.parameters().to_vec(),
+            lr: val!(0.1),
+            ..Default::default()
+        };
+        let input = tensor!([0.5, -1.0, 2.0], requires_grad = false);
+        let target = tensor!([1.0], requires_grad = false);
+
+        let loss = MSE::loss(Reduction::Mean, &model.forward(&input), &target);
+        optimizer.zero_grad();
+        loss.backward();
+        optimizer.step();
+        model
+    })
+    .join()
+    .unwrap();
+
+    let after = model.parameters().mapv(|param| param.value());
+    assert_ne!(before, after);
+}
+
+#[test]
+fn valid_batch_evaluated_across_threads() {
+    let model = Arc::new(model());
+    let inputs = [[0.5, -1.0, 2.0], [1.0, 0.0, -3.0], [0.0, 0.25, 0.75]];
+    let expected: Vec<f64> = inputs
+        .iter()
+        .map(|&[a, b, c]| model.forward(&tensor![a, b, c])[0].value())
+        .collect();
+
+    let outputs: Vec<f64> = thread::scope(|scope| {
+        let handles: Vec<_> = inputs
+            .iter()
+            .map(|&[a, b, c]| {
+                let model = Arc::clone(&model);
+                scope.spawn(move || model.forward(&tensor![a, b, c])[0].value())
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    });
+
+    assert_eq!(outputs, expected);
+}
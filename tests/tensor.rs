@@ -0,0 +1,283 @@
+extern crate micrograd_rs;
+use micrograd_rs::autograd::{hessian, jvp};
+use micrograd_rs::prelude::*;
+use micrograd_rs::{Layer, Linear};
+
+#[test]
+fn valid_matrix_vector_product() {
+    let a = tensor![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]];
+    let b = tensor![1.0, 0.0, -1.0];
+
+    let product: Tensor<Ix1> = a.dot(&b);
+    assert_eq!(product.values(), array![-2.0, -2.0]);
+
+    product.sum().backward();
+    assert_eq!(b.map(|x| x.grad().unwrap().value()), array![5.0, 7.0, 9.0]);
+}
+
+#[test]
+#[should_panic(expected = "Could not multiply")]
+fn invalid_matrix_vector_product() {
+    let a = tensor![[1.0, 2.0], [3.0, 4.0]];
+    let b = tensor![1.0, 2.0, 3.0];
+
+    a.dot(&b);
+}
+
+#[test]
+fn valid_outer_product() {
+    let a = tensor![1.0, 2.0];
+    let b = tensor![3.0, -1.0, 0.5];
+
+    let product = outer(&a, &b);
+    assert_eq!(product.values(), array![[3.0, -1.0, 0.5], [6.0, -2.0, 1.0]]);
+}
+
+#[test]
+fn valid_batched_matrix_product() {
+    let a = array![
+        [[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]],
+        [[-1.0, 0.0], [0.0, 1.0], [2.0, -2.0]]
+    ];
+    let b = array![[1.0, 0.5, -1.0], [2.0, 0.0, 1.0]];
+
+    let product = a.mapv(Value::new).dot(&b.mapv(Value::new));
+    assert_eq!(product.dim(), (2, 3, 3));
+    for (batch, a) in a.outer_iter().enumerate() {
+        assert_eq!(product.values().index_axis(Axis(0), batch), a.dot(&b));
+    }
+}
+
+#[test]
+fn valid_pairwise_batched_matrix_product() {
+    let a = array![[[1.0, 2.0], [3.0, 4.0]], [[0.5, -1.0], [2.0, 0.0]]];
+    let b = array![[[0.0, 1.0], [1.0, 0.0]], [[2.0, 3.0], [-1.0, 1.0]]];
+
+    let product = a.mapv(Value::new).dot(&b.mapv(Value::new));
+    for batch in 0..2 {
+        let expected = a
+            .index_axis(Axis(0), batch)
+            .dot(&b.index_axis(Axis(0), batch));
+        assert_eq!(product.values().index_axis(Axis(0), batch), expected);
+    }
+}
+
+#[test]
+#[should_panic(expected = "Could not multiply batches of shape (2, 2, 2) and (3, 2, 2)")]
+fn invalid_pairwise_batched_matrix_product() {
+    let a = Tensor::from_shape_simple_fn((2, 2, 2), || val!(1.0));
+    let b = Tensor::from_shape_simple_fn((3, 2, 2), || val!(1.0));
+
+    a.dot(&b);
+}
+
+#[test]
+fn valid_linear_on_sequences() {
+    let linear = Linear::new("fc", 3, 2);
+    let input = Array::from_shape_fn((2, 4, 3), |(b, s, f)| (b + s) as f64 - f as f64);
+
+    let output: Tensor<Ix3> = linear.forward(&input.mapv(Value::new));
+    assert_eq!(output.dim(), (2, 4, 2));
+
+    for (seq, output) in input.outer_iter().zip(output.outer_iter()) {
+        let expected: Tensor<Ix2> = linear.forward(&seq.mapv(Value::new));
+        assert_eq!(output.values(), expected.values());
+    }
+
+    let output_value = linear.forward_value(&TensorValue::new(input));
+    let expected = output.values();
+    for (actual, expected) in output_value.value().iter().zip(&expected) {
+        assert!((actual - expected).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn valid_fused_matmul_grads() {
+    let a = array![[1.0, -2.0, 3.0], [0.5, 4.0, -1.0]];
+    let b = array![[2.0, 1.0], [-1.0, 3.0], [0.0, -2.0]];
+
+    let (a_fused, b_fused) = (a.mapv(Value::new), b.mapv(Value::new));
+    let loss: Value = a_fused.dot(&b_fused).mapv(|x| x.powf(2.0)).sum();
+    loss.backward();
+
+    let (a_scalar, b_scalar) = (a.mapv(Value::new), b.mapv(Value::new));
+    let product = Tensor::from_shape_fn((2, 2), |(i, j)| {
+        let terms = a_scalar.row(i).into_iter().zip(b_scalar.column(j));
+        terms.map(|(x, y)| x * y).sum::<Value>()
+    });
+    product.mapv(|x| x.powf(2.0)).sum().backward();
+
+    assert_eq!(a_fused.grads(), a_scalar.grads());
+    assert_eq!(b_fused.grads(), b_scalar.grads());
+}
+
+#[test]
+fn valid_fused_matmul_second_order() {
+    let w = tensor!([[1.0, 2.0], [-1.0, 0.5], [3.0, 0.0]], requires_grad = false);
+    let x = [val!(0.5), val!(-2.0)];
+
+    let hessian = hessian(
+        |x| {
+            let x = Tensor::from_vec(x.to_vec());
+            w.dot(&x).mapv(|y| y.powf(2.0)).sum()
+        },
+        &x,
+    );
+
+    let w = w.values();
+    assert_eq!(hessian, w.t().dot(&w) * 2.0);
+}
+
+#[test]
+fn valid_fused_matmul_tangents() {
+    let a = array![[1.0, 2.0], [3.0, -1.0]];
+    let x = tensor![0.5, 2.0];
+
+    let (output, tangents) = jvp(|x| a.mapv(Value::new).dot(x), &x, &array![1.0, -1.0]);
+    assert_eq!(output.values(), array![4.5, -0.5]);
+    assert_eq!(tangents, array![-1.0, 4.0]);
+}
+
+#[test]
+fn valid_sum_and_mean_axes() {
+    let x = tensor![[[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]]];
+
+    let sum: Tensor<Ix3> = x.sum_axes(&[Axis(1)], true);
+    assert_eq!(sum.values(), array![[[9.0, 12.0]]]);
+
+    let mean: Tensor<Ix1> = x.mean_axes(&[Axis(0), Axis(2)], false);
+    assert_eq!(mean.values(), array![1.5, 3.5, 5.5]);
+
+    (sum.sum() + mean.sum()).backward();
+    assert_eq!(x.grads(), Array::from_elem((1, 3, 2), 1.5));
+}
+
+#[test]
+fn valid_var_axes() {
+    let data = array![[1.0, 4.0, -2.0, 0.5], [3.0, 3.0, 1.0, -6.0]];
+    let x = data.mapv(Value::new);
+
+    let var: Tensor<Ix1> = x.var_axes(&[Axis(1)], 1, false);
+    for (actual, expected) in var.values().iter().zip(data.var_axis(Axis(1), 1.0)) {
+        assert!((actual - expected).abs() < 1e-12);
+    }
+
+    var.sum().backward();
+    let mean = data.mean_axis(Axis(1)).unwrap().insert_axis(Axis(1));
+    let expected = (&data - &mean) * 2.0 / 3.0;
+    for (actual, expected) in x.grads().iter().zip(&expected) {
+        assert!((actual - expected).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn valid_logsumexp_axes() {
+    let data = array![[1000.0, 1001.0, 999.0], [-1.0, 0.0, 2.0]];
+    let x = data.mapv(Value::new);
+
+    let logsumexp: Tensor<Ix2> = x.logsumexp_axes(&[Axis(1)], true);
+    assert_eq!(logsumexp.dim(), (2, 1));
+    logsumexp.sum().backward();
+
+    for (row, (lse, grads)) in data
+        .outer_iter()
+        .zip(logsumexp.values().iter().zip(x.grads().outer_iter()))
+    {
+        let max = row.fold(f64::MIN, |max, x| max.max(*x));
+        let sum = row.mapv(|x| (x - max).exp()).sum();
+        assert!((lse - (max + sum.ln())).abs() < 1e-12);
+
+        let softmax = row.mapv(|x| (x - max).exp() / sum);
+        for (actual, expected) in grads.iter().zip(&softmax) {
+            assert!((actual - expected).abs() < 1e-12);
+        }
+    }
+}
+
+#[test]
+fn valid_max_and_argmax() {
+    let x = tensor![[1.0, 5.0, 2.0], [7.0, -1.0, 7.0]];
+
+    let max: Tensor<Ix1> = x.max_axes(&[Axis(1)], false);
+    assert_eq!(max.values(), array![5.0, 7.0]);
+    assert_eq!(x.argmax::<Ix1>(Axis(1), false), array![1, 0]);
+    assert_eq!(x.argmax::<Ix2>(Axis(0), true), array![[1, 0, 1]]);
+
+    max.sum().backward();
+    assert_eq!(x.grads(), array![[0.0, 1.0, 0.0], [1.0, 0.0, 0.0]]);
+}
+
+#[test]
+fn valid_norm() {
+    let x = tensor![[3.0, 0.0], [0.0, -4.0]];
+
+    let norm = x.norm(2.0);
+    assert!((norm.value() - 5.0).abs() < 1e-12);
+    assert!((x.norm(1.0).value() - 7.0).abs() < 1e-12);
+
+    norm.backward();
+    let expected = array![[0.6, 0.0], [0.0, -0.8]];
+    for (actual, expected) in x.grads().iter().zip(&expected) {
+        assert!((actual - expected).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn valid_norm_at_origin() {
+    for p in [0.5, 1.0, 2.0] {
+        let x = tensor![[0.0, 0.0], [0.0, 0.0]];
+
+        let norm = x.norm(p);
+        norm.backward();
+
+        assert_eq!(norm.value(), 0.0);
+        assert_eq!(x.grads(), Array2::<f64>::zeros((2, 2)));
+    }
+}
+
+#[test]
+#[should_panic(expected = "Cannot take the maximum of an empty axis")]
+fn invalid_argmax_of_empty_axis() {
+    let x = Tensor::from_shape_simple_fn((2, 0), || val!(1.0));
+
+    x.argmax::<Ix1>(Axis(1), false);
+}
+
+#[test]
+#[should_panic(expected = "Cannot reduce over axes")]
+fn invalid_repeated_reduction_axes() {
+    let x = tensor![[1.0, 2.0], [3.0, 4.0]];
+
+    let _: Tensor<Ix0> = x.sum_axes(&[Axis(1), Axis(1)], false);
+}
+
+#[test]
+fn valid_f64_conversions() {
+    let data = array![[1.0, -2.0], [0.5, 3.0]];
+    let x = Tensor::from_f64(&data, true);
+    let constant: Tensor<Ix1> = Tensor::from_f64(&data.row(0), false);
+
+    assert_eq!(x.values(), data);
+    assert!(x.iter().all(Value::should_compute_grad));
+
+    let single: Tensor<Ix2, f32> = Tensor::from_f64(&data, false);
+    assert_eq!(single.values(), data.mapv(|x| x as f32));
+    assert!(!constant.iter().any(Value::should_compute_grad));
+
+    (&x * &x).sum().backward();
+    assert_eq!(x.grads(), &data * 2.0);
+    assert_eq!(constant.grads(), array![0.0, 0.0]);
+
+    x.zero_grad();
+    assert_eq!(x.grads(), Array2::<f64>::zeros((2, 2)));
+
+    x.set_values(&array![[0.0, 1.0], [2.0, 3.0]]);
+    assert_eq!(x.values(), array![[0.0, 1.0], [2.0, 3.0]]);
+}
+
+#[test]
+#[should_panic(expected = "Cannot set values of shape [3] on a tensor of shape [2]")]
+fn invalid_set_values_shape() {
+    let x = Tensor::from_f64(&array![1.0, 2.0], true);
+    x.set_values(&array![1.0, 2.0, 3.0]);
+}
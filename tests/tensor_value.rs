@@ -0,0 +1,221 @@
+extern crate micrograd_rs;
+use approx::assert_abs_diff_eq;
+use micrograd_rs::activations as Activation;
+use micrograd_rs::prelude::*;
+use micrograd_rs::{Layer, Linear, Sequential};
+
+mod common;
+use common::assert_all_close;
+
+#[test]
+fn valid_matmul() {
+    let a = array![[1.0, -2.0, 3.0], [0.5, 4.0, -1.0]];
+    let b = array![[2.0, 1.0], [-1.0, 3.0], [0.0, -2.0]];
+
+    let a_value = TensorValue::new(a.clone());
+    let b_value = TensorValue::new(b.clone());
+    let out = a_value.dot(&b_value);
+    assert_all_close(&out.value(), &a.dot(&b), 1e-9);
+    out.powf(2.0).sum().backward();
+
+    let a_tensor = a.mapv(Value::new);
+    let b_tensor = b.mapv(Value::new);
+    let loss: Value = a_tensor.dot(&b_tensor).mapv(|x| x.powf(2.0)).sum();
+    loss.backward();
+
+    assert_all_close(&a_value.grad().unwrap(), &a_tensor.grads(), 1e-9);
+    assert_all_close(&b_value.grad().unwrap(), &b_tensor.grads(), 1e-9);
+}
+
+#[test]
+fn valid_vector_matmul() {
+    let x = TensorValue::new(array![1.0, 2.0]);
+    let w = TensorValue::new(array![[3.0, -1.0, 0.5], [2.0, 1.0, -4.0]]);
+
+    let out = x.dot(&w);
+    assert_all_close(&out.value(), &array![7.0, 1.0, -7.5], 1e-9);
+    out.sum().backward();
+
+    assert_all_close(&x.grad().unwrap(), &array![2.5, -1.0], 1e-9);
+    assert_all_close(
+        &w.grad().unwrap(),
+        &array![[1.0, 1.0, 1.0], [2.0, 2.0, 2.0]],
+        1e-9,
+    );
+}
+
+#[test]
+fn valid_broadcast_grads() {
+    let x = TensorValue::new(array![[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]]);
+    let b = TensorValue::new(array![10.0, -10.0]);
+
+    let out = (&x + &b) * 2.0;
+    assert_all_close(
+        &out.value(),
+        &array![[22.0, -16.0], [26.0, -12.0], [30.0, -8.0]],
+        1e-9,
+    );
+    out.sum().backward();
+
+    assert_all_close(&x.grad().unwrap(), &Array::from_elem((3, 2), 2.0), 1e-9);
+    assert_all_close(&b.grad().unwrap(), &array![6.0, 6.0], 1e-9);
+}
+
+#[test]
+fn valid_dyn_broadcast_of_higher_rank_rhs() {
+    let x = TensorValue::new(arr0(3.0).into_dyn());
+    let b = TensorValue::new(array![[1.0, 2.0], [3.0, 4.0]].into_dyn());
+
+    let out = &x * &b;
+    assert_all_close(
+        &out.value(),
+        &array![[3.0, 6.0], [9.0, 12.0]].into_dyn(),
+        1e-9,
+    );
+    out.sum().backward();
+
+    assert_all_close(&x.grad().unwrap(), &arr0(10.0).into_dyn(), 1e-9);
+    assert_all_close(
+        &b.grad().unwrap(),
+        &Array::from_elem((2, 2), 3.0).into_dyn(),
+        1e-9,
+    );
+}
+
+#[test]
+fn valid_elementwise_grads() {
+    let a = array![0.5, -1.5, 2.0];
+    let b = array![1.5, 2.0, -0.5];
+
+    let a_value = TensorValue::new(a.clone());
+    let b_value = TensorValue::new(b.clone());
+    let out_value =
+        (&a_value * &b_value).tanh() - (&a_value / &b_value).sigmoid() + a_value.exp().log().relu();
+    out_value.mean().backward();
+
+    let a_tensor = a.mapv(Value::new);
+    let b_tensor = b.mapv(Value::new);
+    let out = (&a_tensor * &b_tensor).mapv(|x| x.tanh())
+        - (&a_tensor / &b_tensor).mapv(|x| x.sigmoid())
+        + a_tensor.mapv(|x| x.exp().log().max(Value::new(0.0)));
+    (out.sum() / 3.0).backward();
+
+    assert_all_close(&out_value.value(), &out.mapv(|x| x.value()), 1e-9);
+    assert_all_close(&a_value.grad().unwrap(), &a_tensor.grads(), 1e-9);
+    assert_all_close(&b_value.grad().unwrap(), &b_tensor.grads(), 1e-9);
+}
+
+#[test]
+fn valid_sum_axis_grads() {
+    let x = TensorValue::new(array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+
+    let rows = x.sum_axis(Axis(1));
+    assert_all_close(&rows.value(), &array![6.0, 15.0], 1e-9);
+
+    let loss =
+        (&rows * &TensorValue::constant(array![1.0, -1.0])).sum() + x.mean_axis(Axis(0)).sum();
+    loss.backward();
+
+    assert_all_close(
+        &x.grad().unwrap(),
+        &array![[1.5, 1.5, 1.5], [-0.5, -0.5, -0.5]],
+        1e-9,
+    );
+}
+
+#[test]
+fn valid_model_forward_value() {
+    let model: Sequential<Ix2> = sequential!(
+        Ix2,
+        [
+            Linear::new("fc1", 3, 4),
+            Activation::ReLU,
+            Linear::new("fc2", 4, 2),
+            Activation::Softmax(1)
+        ]
+    );
+    let input = array![[2.0, 3.0, -1.0], [0.5, -2.0, 1.0]];
+
+    let output = model.forward(&input.mapv(Value::new));
+    (output.mapv(|x| x.powf(2.0)).sum()).backward();
+    let expected = model
+        .parameters()
+        .map(|param| param.grad().unwrap().value());
+    model.parameters().iter().for_each(Value::zero_grad);
+
+    let input = TensorValue::new(input);
+    let output_value = model.forward_value(&input);
+    assert_all_close(&output_value.value(), &output.mapv(|x| x.value()), 1e-9);
+    output_value.powf(2.0).sum().backward();
+
+    let actual = model
+        .parameters()
+        .map(|param| param.grad().unwrap().value());
+    assert_all_close(&actual, &expected, 1e-9);
+    assert!(input.grad().is_some());
+}
+
+#[test]
+fn valid_layer_forward_value() {
+    let linear = Linear::new("fc", 2, 2);
+    let input = TensorValue::constant(array![1.0, -1.0]);
+
+    let output: TensorValue<Ix1> = linear.forward_value(&input);
+    output.sum().backward();
+
+    assert!(input.grad().is_none());
+    assert_all_close(
+        &linear.weights.grads(),
+        &array![[1.0, -1.0], [1.0, -1.0]],
+        1e-9,
+    );
+    assert_all_close(&linear.biases.grads(), &array![1.0, 1.0], 1e-9);
+}
+
+#[test]
+fn valid_no_grad_tensor_value() {
+    let x = TensorValue::new(array![1.0, 2.0]);
+
+    let out = {
+        let _guard = micrograd_rs::autograd::no_grad();
+        (&x * 3.0).sum()
+    };
+
+    assert!(out.is_leaf());
+    assert!(!out.should_compute_grad());
+}
+
+#[test]
+#[should_panic(expected = "Backward can only start from a single element")]
+fn invalid_backward_from_vector() {
+    let x = TensorValue::new(array![1.0, 2.0]);
+    x.exp().backward();
+}
+
+#[test]
+fn valid_batched_matmul() {
+    let a = Array::from_shape_fn((2, 3, 2), |(b, m, k)| (b * 6 + m * 2 + k) as f64 - 4.0);
+    let b = array![[1.0, -2.0, 0.5], [3.0, 1.0, -1.0]];
+    let c = Array::from_shape_fn((2, 2, 3), |(b, k, n)| (b + k * n) as f64 * 0.5);
+
+    let a_value = TensorValue::new(a.clone());
+    let b_value = TensorValue::new(b.clone());
+    let c_value = TensorValue::new(c.clone());
+    let out = &a_value.dot(&b_value) * &a_value.dot(&c_value);
+    out.sum().backward();
+
+    let a_tensor = a.mapv(Value::new);
+    let b_tensor = b.mapv(Value::new);
+    let c_tensor = c.mapv(Value::new);
+    let loss: Value = (&a_tensor.dot(&b_tensor) * &a_tensor.dot(&c_tensor)).sum();
+    loss.backward();
+
+    assert_abs_diff_eq!(
+        out.sum().value().into_scalar(),
+        loss.value(),
+        epsilon = 1e-9
+    );
+    assert_all_close(&a_value.grad().unwrap(), &a_tensor.grads(), 1e-9);
+    assert_all_close(&b_value.grad().unwrap(), &b_tensor.grads(), 1e-9);
+    assert_all_close(&c_value.grad().unwrap(), &c_tensor.grads(), 1e-9);
+}
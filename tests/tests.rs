@@ -1,4 +1,6 @@
 mod activations;
+mod autograd;
+mod common;
 mod criterions;
 mod layers;
 mod lr_schedulers;
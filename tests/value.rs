@@ -11,7 +11,7 @@ fn nth_derivative(n: usize, x: Value, y: Value) -> f64 {
     (0..n)
         .fold(y, |d: Value, _| {
             x.zero_grad();
-            d.backward();
+            d.backward_with(true);
             x.grad().unwrap_or_else(Value::zero)
         })
         .value()
@@ -123,6 +123,28 @@ fn valid_div_assign_grads() {
     assert_eq!(x.value(), 3.0);
 }
 
+#[test]
+fn valid_integer_operands() {
+    let x = val!(3.0);
+
+    let y = (&x * &2 + 1) / 7 - x.powf(2);
+    y.backward();
+
+    assert_eq!(y.value(), -8.0);
+    assert_eq!(x.grad().unwrap().value(), 2.0 / 7.0 - 6.0);
+}
+
+#[test]
+fn valid_mixed_scalar_operands() {
+    let x = val!(3.0);
+
+    let y = (&x * &2u32 + 1u8) * 1.5f32 - 3i8;
+    y.backward();
+
+    assert_eq!(y.value(), 7.5);
+    assert_eq!(x.grad().unwrap().value(), 3.0);
+}
+
 #[test]
 fn valid_pow_grads() {
     let x = Value::from(3.0);
@@ -158,6 +180,145 @@ fn valid_exp_grads() {
     }
 }
 
+#[test]
+fn valid_log_grads() {
+    let x = Value::from(3.0);
+    let y = x.log();
+
+    let actual_derivatives = [3.0_f64.ln(), 1.0 / 3.0, -1.0 / 9.0, 2.0 / 27.0];
+    let n = actual_derivatives.len();
+
+    for i in 0..n {
+        assert_abs_diff_eq!(
+            nth_derivative(i, x.clone(), y.clone()),
+            actual_derivatives[i],
+            epsilon = 1e-6
+        );
+    }
+}
+
+#[test]
+fn valid_same_operand_grads() {
+    let x = Value::from(3.0);
+    let y = &x * &x;
+
+    let actual_derivatives = [9.0, 6.0, 2.0, 0.0];
+    let n = actual_derivatives.len();
+
+    for i in 0..n {
+        assert_eq!(
+            nth_derivative(i, x.clone(), y.clone()),
+            actual_derivatives[i]
+        );
+    }
+}
+
+#[test]
+fn valid_backward_without_graph() {
+    let x = Value::from(3.0);
+    let y = x.powf(3.0);
+    y.backward();
+
+    let grad = x.grad().unwrap();
+    assert_eq!(grad.value(), 27.0);
+
+    x.zero_grad();
+    grad.backward();
+
+    assert!(x.grad().is_none());
+}
+
+fn assert_derivatives(x: f64, op: fn(&Value) -> Value, actual_derivatives: &[f64]) {
+    let x = Value::from(x);
+    let y = op(&x);
+
+    for (i, actual) in actual_derivatives.iter().enumerate() {
+        assert_abs_diff_eq!(
+            nth_derivative(i, x.clone(), y.clone()),
+            *actual,
+            epsilon = 1e-9
+        );
+    }
+}
+
+#[test]
+fn valid_sin_and_cos_grads() {
+    let x: f64 = 0.7;
+
+    assert_derivatives(x, Value::sin, &[x.sin(), x.cos(), -x.sin(), -x.cos()]);
+    assert_derivatives(x, Value::cos, &[x.cos(), -x.sin(), -x.cos(), x.sin()]);
+}
+
+#[test]
+fn valid_tanh_grads() {
+    let x: f64 = -0.4;
+    let y = x.tanh();
+
+    let actual_derivatives = [y, 1.0 - y.powi(2), -2.0 * y * (1.0 - y.powi(2))];
+    assert_derivatives(x, Value::tanh, &actual_derivatives);
+}
+
+#[test]
+fn valid_sigmoid_and_softplus_grads() {
+    let x: f64 = 1.3;
+    let s = 1.0 / (1.0 + (-x).exp());
+
+    let actual_derivatives = [s, s * (1.0 - s), s * (1.0 - s) * (1.0 - 2.0 * s)];
+    assert_derivatives(x, Value::sigmoid, &actual_derivatives);
+
+    let actual_derivatives = [(1.0 + x.exp()).ln(), s, s * (1.0 - s)];
+    assert_derivatives(x, Value::softplus, &actual_derivatives);
+}
+
+#[test]
+fn valid_abs_grads() {
+    assert_derivatives(-2.5, Value::abs, &[2.5, -1.0, 0.0]);
+    assert_derivatives(2.5, Value::abs, &[2.5, 1.0, 0.0]);
+}
+
+#[test]
+fn valid_erf_grads() {
+    let x: f64 = 0.5;
+    let derivative = (2.0 / std::f64::consts::PI.sqrt()) * (-x.powi(2)).exp();
+
+    let actual_derivatives = [0.5204998778130465, derivative, -2.0 * x * derivative];
+    assert_derivatives(x, Value::erf, &actual_derivatives);
+}
+
+#[test]
+fn valid_erf_values() {
+    let inputs = [1.0, 2.9, 3.5, -2.0, 5.0];
+    let actuals = [
+        0.8427007929497149,
+        0.9999589021219005,
+        0.9999992569016276,
+        -0.9953222650189527,
+        0.9999999999984626,
+    ];
+
+    for (input, actual) in inputs.into_iter().zip(actuals) {
+        assert_abs_diff_eq!(Value::from(input).erf().value(), actual, epsilon = 1e-14);
+    }
+}
+
+#[test]
+fn valid_clamp_grads() {
+    assert_derivatives(0.5, |x| x.clamp(-1.0, 1.0), &[0.5, 1.0, 0.0]);
+    assert_derivatives(3.0, |x| x.clamp(-1.0, 1.0), &[1.0, 0.0]);
+    assert_derivatives(-3.0, |x| x.clamp(-1.0, 1.0), &[-1.0, 0.0]);
+}
+
+#[test]
+fn valid_log1p_and_expm1_grads() {
+    let x: f64 = 1e-10;
+
+    let actual_derivatives = [x.ln_1p(), 1.0 / (1.0 + x), -1.0 / (1.0 + x).powi(2)];
+    assert_derivatives(x, Value::log1p, &actual_derivatives);
+
+    let actual_derivatives = [x.exp_m1(), x.exp(), x.exp()];
+    assert_derivatives(x, Value::expm1, &actual_derivatives);
+}
+
 #[test]
 fn valid_drop() {
     let mut a = Value::from(0.01);
@@ -168,3 +329,25 @@ fn valid_drop() {
 
     assert_eq!(drop(a), ());
 }
+
+#[test]
+fn valid_backward_on_deep_graph() {
+    let x = Value::from(0.01);
+    let mut y = x.clone();
+
+    for _ in 0..500_000 {
+        y = &y + &1.0;
+    }
+    y.backward();
+
+    assert_eq!(x.grad().unwrap().value(), 1.0);
+}
+
+#[test]
+fn valid_requires_grad_follows_operands() {
+    let constant = val!(-0.5, requires_grad = false);
+    let x = Value::from(2.0);
+
+    assert!(!(&constant - &1.0).should_compute_grad());
+    assert!((&constant * &x).should_compute_grad());
+}